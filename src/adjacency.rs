@@ -0,0 +1,82 @@
+use std::collections::{hash_map::Entry, HashMap};
+
+use crate::{Point, Triangle};
+
+/// An undirected edge between two vertex ids, with the lower id first
+pub(crate) type Edge = (usize, usize);
+
+/// Connectivity between facets which share vertices and edges
+///
+/// As in [`StlFile::split_islands`](crate::StlFile::split_islands), vertices
+/// are identified by their [`Point`], so facets touch wherever their vertices
+/// compare equal.
+#[derive(Debug)]
+pub(crate) struct Adjacency {
+    /// The id of each vertex of each facet, in winding order
+    pub facets: Vec<[usize; 3]>,
+
+    /// The position of each vertex id
+    pub points: Vec<Point>,
+
+    /// The facets using each edge, along with whether each traverses it from
+    /// its lower to its higher vertex id. Edges of degenerate facets which
+    /// join a vertex to itself are not included.
+    pub edges: HashMap<Edge, Vec<(usize, bool)>>,
+}
+
+impl Adjacency {
+    pub fn new(triangles: impl Iterator<Item = Triangle>) -> Self {
+        let mut ids = HashMap::new();
+        let mut points = Vec::new();
+
+        let mut id = |point: Point| match ids.entry(point) {
+            Entry::Occupied(val) => *val.get(),
+            Entry::Vacant(ptr) => {
+                points.push(point);
+                *ptr.insert(points.len() - 1)
+            }
+        };
+
+        let facets = triangles
+            .map(|t| [id(t.v0), id(t.v1), id(t.v2)])
+            .collect::<Vec<_>>();
+
+        let mut edges: HashMap<Edge, Vec<(usize, bool)>> = HashMap::new();
+
+        for (facet, vertices) in facets.iter().enumerate() {
+            for &(a, b) in &facet_edges(*vertices) {
+                if a != b {
+                    edges
+                        .entry((a.min(b), a.max(b)))
+                        .or_default()
+                        .push((facet, a < b));
+                }
+            }
+        }
+
+        Self {
+            facets,
+            points,
+            edges,
+        }
+    }
+
+    /// Facets sharing an edge with `facet` which is used by exactly two
+    /// facets, alongside whether they traverse that edge in the same direction
+    pub fn manifold_neighbors(&self, facet: usize) -> impl Iterator<Item = (usize, bool)> + '_ {
+        IntoIterator::into_iter(facet_edges(self.facets[facet]))
+            .filter(|&(a, b)| a != b)
+            .filter_map(
+                move |(a, b)| match *self.edges[&(a.min(b), a.max(b))].as_slice() {
+                    [(f0, d0), (f1, d1)] if f0 == facet && f1 != facet => Some((f1, d0 == d1)),
+                    [(f0, d0), (f1, d1)] if f1 == facet && f0 != facet => Some((f0, d0 == d1)),
+                    _ => None,
+                },
+            )
+    }
+}
+
+/// The directed edges of a facet, following its winding
+pub(crate) fn facet_edges([a, b, c]: [usize; 3]) -> [(usize, usize); 3] {
+    [(a, b), (b, c), (c, a)]
+}
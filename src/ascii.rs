@@ -1,4 +1,7 @@
-use crate::{Normal, StlFile};
+use crate::{
+    error::{Location, ParseError, ParseErrorKind, ParseWarning},
+    Normal, ParseOptions, StlFile, StlResult,
+};
 
 #[derive(Debug)]
 pub struct AsciiParser<'a> {
@@ -6,52 +9,377 @@ pub struct AsciiParser<'a> {
     cursor: usize,
     normals: Vec<Normal>,
     vertices: Vec<f32>,
+    options: ParseOptions,
+    warnings: Vec<ParseWarning>,
+    /// Number of facets parsed across every solid, used to enforce limits
+    num_of_facets: usize,
+    #[cfg(feature = "rayon")]
+    parallel: bool,
 }
 
+/// Facet lists shorter than this many bytes are not worth splitting across threads
+#[cfg(feature = "rayon")]
+const MIN_PARALLEL_LEN: usize = 64 * 1024;
+
 impl<'a> AsciiParser<'a> {
-    pub fn new(buffer: &'a [u8]) -> Result<Self, ()> {
+    pub fn new(buffer: &'a [u8], options: ParseOptions) -> StlResult<Self> {
+        options.check_input_len(buffer.len())?;
+
         let mut ascii_parser = Self {
             buffer,
             cursor: 0,
             normals: Vec::new(),
             vertices: Vec::new(),
+            options,
+            warnings: Vec::new(),
+            num_of_facets: 0,
+            #[cfg(feature = "rayon")]
+            parallel: false,
         };
 
         ascii_parser.skip_whitespace();
-        ascii_parser.expect_bytes(b"solid ")?;
-        ascii_parser.skip_whitespace();
+        ascii_parser.expect_solid()?;
 
         Ok(ascii_parser)
     }
 
-    pub fn parse(mut self) -> StlFile {
-        let _name = self.read_string();
+    /// Split the facets of each solid at `facet` boundaries and parse them on
+    /// the rayon thread pool
+    #[cfg(feature = "rayon")]
+    pub fn parallel(mut self) -> Self {
+        self.parallel = true;
+        self
+    }
+
+    /// Parse every solid in the buffer, merging them into a single file which
+    /// takes the name of the first
+    pub fn parse(self) -> StlResult<(StlFile, Vec<ParseWarning>)> {
+        let (solids, warnings) = self.parse_solids()?;
+        let mut solids = solids.into_iter();
+
+        // `parse_solids` always yields at least one solid
+        let mut file = solids.next().unwrap_or_else(StlFile::new);
+
+        for solid in solids {
+            file.append(solid);
+        }
+
+        Ok((file, warnings))
+    }
+
+    /// Parse each `solid ... endsolid` block in the buffer into its own file
+    pub fn parse_solids(mut self) -> StlResult<(Vec<StlFile>, Vec<ParseWarning>)> {
+        let mut solids = vec![self.parse_solid()?];
+
+        while self.next_solid()? {
+            solids.push(self.parse_solid()?);
+        }
+
+        Ok((solids, self.warnings))
+    }
+
+    /// Consume the `solid` keyword. In strict mode, it must be followed by a space
+    fn expect_solid(&mut self) -> Result<(), ParseError> {
+        let start = self.cursor;
+        self.expect_bytes(b"solid")?;
+
+        if self.options.strict {
+            if self.buffer.get(self.cursor) != Some(&b' ') {
+                return Err(self.error(ParseErrorKind::MissingName, start));
+            }
+
+            self.cursor += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Skip the optional name following `endsolid`, returning whether another
+    /// solid follows. Anything else after the last solid is ignored
+    fn next_solid(&mut self) -> Result<bool, ParseError> {
+        if self.options.strict {
+            self.skip_whitespace();
+
+            if !self.next_is_keyword(b"solid") {
+                self.read_string();
+            }
+        } else {
+            self.skip_line();
+        }
 
         self.skip_whitespace();
 
+        if !self.next_is_keyword(b"solid") {
+            if self.cursor < self.buffer.len() {
+                self.warn(ParseWarning::TrailingContent {
+                    offset: self.cursor,
+                });
+            }
+
+            return Ok(false);
+        }
+
+        self.expect_solid()?;
+
+        Ok(true)
+    }
+
+    /// Read the name of a solid, directly after the `solid` keyword
+    ///
+    /// In strict mode, this is a single token, or empty if the line ends
+    /// first. Otherwise, it is the remainder of the line, which may contain
+    /// spaces or be missing entirely.
+    fn read_name(&mut self) -> String {
+        if self.options.strict {
+            self.skip_blanks();
+            return String::from_utf8_lossy(self.read_string()).into_owned();
+        }
+
+        let start = self.cursor;
+        let mut end = self.cursor;
+
+        loop {
+            self.skip_blanks();
+
+            if self.cursor >= self.buffer.len()
+                || self.next_is_whitespace()
+                || self.next_is_keyword(b"facet")
+                || self.next_is_keyword(b"endsolid")
+            {
+                break;
+            }
+
+            self.read_string();
+            end = self.cursor;
+        }
+
+        let name = String::from_utf8_lossy(&self.buffer[start..end])
+            .trim()
+            .to_owned();
+
+        if name.is_empty() {
+            self.warn(ParseWarning::MissingName { offset: start });
+        }
+
+        name
+    }
+
+    fn parse_solid(&mut self) -> StlResult<StlFile> {
+        let name = self.read_name();
+
+        self.skip_whitespace();
+
+        #[cfg(feature = "rayon")]
+        if self.parallel {
+            self.par_read_triangles()?;
+        }
+
         while !self.consume_if_next_is_bytes(b"endsolid") {
-            self.read_triangle().unwrap();
+            if self.cursor >= self.buffer.len() {
+                return Err(self.eof_error().into());
+            }
+
+            if !self.next_is_bytes(b"facet") {
+                return Err(self
+                    .error(
+                        ParseErrorKind::UnexpectedToken {
+                            found: self.peek_token(),
+                        },
+                        self.cursor,
+                    )
+                    .into());
+            }
+
+            self.read_triangle()?;
+        }
+
+        Ok(StlFile {
+            attributes: vec![0; self.normals.len()],
+            normals: std::mem::take(&mut self.normals),
+            vertices: std::mem::take(&mut self.vertices),
+            header: [0; 80],
+            name,
+        })
+    }
+
+    /// Read every facet up to the next `endsolid` in parallel, leaving the
+    /// cursor at `endsolid`
+    ///
+    /// If there is no `endsolid`, nothing is read and the sequential parser is
+    /// left to report the error.
+    #[cfg(feature = "rayon")]
+    fn par_read_triangles(&mut self) -> StlResult<()> {
+        use rayon::prelude::*;
+
+        let end = match self.buffer[self.cursor..]
+            .windows(b"endsolid".len())
+            .position(|window| window == b"endsolid")
+        {
+            Some(idx) => self.cursor + idx,
+            None => return Ok(()),
+        };
+
+        if end - self.cursor < MIN_PARALLEL_LEN {
+            return Ok(());
         }
 
-        StlFile {
-            normals: self.normals,
-            vertices: self.vertices,
+        let num_of_chunks = rayon::current_num_threads() * 4;
+        let chunk_len = (end - self.cursor) / num_of_chunks + 1;
+
+        let mut boundaries = vec![self.cursor];
+        while let Some(&last) = boundaries.last() {
+            match self.next_facet_boundary(last + chunk_len, end) {
+                Some(boundary) => boundaries.push(boundary),
+                None => break,
+            }
+        }
+        boundaries.push(end);
+
+        let chunks = boundaries
+            .par_windows(2)
+            .map(|range| -> StlResult<Self> {
+                let mut parser = AsciiParser {
+                    buffer: self.buffer,
+                    cursor: range[0],
+                    normals: Vec::new(),
+                    vertices: Vec::new(),
+                    options: self.options,
+                    warnings: Vec::new(),
+                    // facets in earlier chunks are not yet known, so this
+                    // only catches a single chunk exceeding the limit
+                    num_of_facets: self.num_of_facets,
+                    parallel: false,
+                };
+
+                parser.skip_whitespace();
+
+                while parser.cursor < range[1] {
+                    if !parser.next_is_bytes(b"facet") {
+                        return Err(parser
+                            .error(
+                                ParseErrorKind::UnexpectedToken {
+                                    found: parser.peek_token(),
+                                },
+                                parser.cursor,
+                            )
+                            .into());
+                    }
+
+                    parser.read_triangle()?;
+                }
+
+                Ok(parser)
+            })
+            .collect::<Vec<_>>();
+
+        for chunk in chunks {
+            let parser = chunk?;
+
+            self.num_of_facets += parser.normals.len();
+            self.options.check_facet_count(self.num_of_facets)?;
+
+            self.normals.extend(parser.normals);
+            self.vertices.extend(parser.vertices);
+            self.cursor = parser.cursor;
+
+            for warning in parser.warnings {
+                self.warn(warning);
+            }
         }
+
+        Ok(())
+    }
+
+    /// The start of the first `facet` keyword at or after `from` and before `end`
+    #[cfg(feature = "rayon")]
+    fn next_facet_boundary(&self, from: usize, end: usize) -> Option<usize> {
+        (from..end.saturating_sub(b"facet".len())).find(|&idx| {
+            self.buffer[idx - 1].is_ascii_whitespace()
+                && &self.buffer[idx..(idx + b"facet".len())] == b"facet"
+                && self.buffer[idx + b"facet".len()].is_ascii_whitespace()
+        })
     }
 
-    fn expect_bytes(&mut self, s: &'static [u8]) -> Result<(), ()> {
-        if &self.buffer[self.cursor..(self.cursor + s.len())] != s {
-            return Err(());
+    fn warn(&mut self, warning: ParseWarning) {
+        let is_duplicate = warning.is_reported_once()
+            && self
+                .warnings
+                .iter()
+                .any(|w| std::mem::discriminant(w) == std::mem::discriminant(&warning));
+
+        if !is_duplicate {
+            self.warnings.push(warning);
+        }
+    }
+
+    /// Consume `len` bytes which matched keyword `s`, warning if they did not
+    /// match exactly
+    fn consume_keyword(&mut self, s: &'static [u8]) {
+        if self.buffer.get(self.cursor..(self.cursor + s.len())) != Some(s) {
+            self.warn(ParseWarning::KeywordCase {
+                offset: self.cursor,
+            });
         }
 
         self.cursor += s.len();
+    }
+
+    fn error(&self, kind: ParseErrorKind, offset: usize) -> ParseError {
+        ParseError::new(kind, offset).with_location(Location::from_offset(self.buffer, offset))
+    }
+
+    /// The whitespace-delimited token starting at the cursor, without consuming it
+    fn peek_token(&self) -> String {
+        let rest = self.buffer.get(self.cursor..).unwrap_or(&[]);
+        let len = rest
+            .iter()
+            .position(u8::is_ascii_whitespace)
+            .unwrap_or(rest.len());
+
+        String::from_utf8_lossy(&rest[..len]).into_owned()
+    }
+
+    fn eof_error(&self) -> ParseError {
+        self.error(ParseErrorKind::UnexpectedEof, self.cursor)
+    }
+
+    fn expect_bytes(&mut self, s: &'static [u8]) -> Result<(), ParseError> {
+        if self.cursor >= self.buffer.len() {
+            return Err(self.eof_error());
+        }
+
+        if !self.next_is_bytes(s) {
+            return Err(self.error(
+                ParseErrorKind::ExpectedKeyword {
+                    expected: std::str::from_utf8(s).unwrap_or_default(),
+                    found: self.peek_token(),
+                },
+                self.cursor,
+            ));
+        }
+
+        self.consume_keyword(s);
 
         Ok(())
     }
 
+    /// Whether the buffer continues with `s`, ignoring case outside of strict mode
+    fn next_is_bytes(&self, s: &'static [u8]) -> bool {
+        match self.buffer.get(self.cursor..(self.cursor + s.len())) {
+            Some(bytes) => bytes == s || (!self.options.strict && bytes.eq_ignore_ascii_case(s)),
+            None => false,
+        }
+    }
+
+    /// Whether the next token is exactly `s`
+    fn next_is_keyword(&self, s: &'static [u8]) -> bool {
+        self.next_is_bytes(s)
+            && !matches!(self.buffer.get(self.cursor + s.len()), Some(b) if !b.is_ascii_whitespace())
+    }
+
     fn consume_if_next_is_bytes(&mut self, s: &'static [u8]) -> bool {
-        if &self.buffer[self.cursor..(self.cursor + s.len())] == s {
-            self.cursor += s.len();
+        if self.next_is_bytes(s) {
+            self.consume_keyword(s);
             true
         } else {
             false
@@ -59,7 +387,14 @@ impl<'a> AsciiParser<'a> {
     }
 
     fn next_is_whitespace(&self) -> bool {
-        self.buffer[self.cursor].is_ascii_whitespace()
+        matches!(self.buffer.get(self.cursor), Some(b) if b.is_ascii_whitespace())
+    }
+
+    /// Advance the cursor to the next whitespace character or the end of the buffer
+    fn skip_to_whitespace(&mut self) {
+        while self.cursor < self.buffer.len() && !self.next_is_whitespace() {
+            self.cursor += 1;
+        }
     }
 
     fn consume_next_if_whitespace(&mut self) -> bool {
@@ -76,24 +411,40 @@ impl<'a> AsciiParser<'a> {
         while self.consume_next_if_whitespace() {}
     }
 
-    fn read_string(&mut self) -> &'a [u8] {
-        let cursor_start = self.cursor;
+    /// Advance the cursor past any spaces and tabs, stopping at the end of the line
+    fn skip_blanks(&mut self) {
+        while matches!(self.buffer.get(self.cursor), Some(b' ') | Some(b'\t')) {
+            self.cursor += 1;
+        }
+    }
 
-        while !self.next_is_whitespace() {
+    /// Advance the cursor past the next newline or to the end of the buffer
+    fn skip_line(&mut self) {
+        while let Some(&b) = self.buffer.get(self.cursor) {
             self.cursor += 1;
+
+            if b == b'\n' {
+                break;
+            }
         }
+    }
+
+    fn read_string(&mut self) -> &'a [u8] {
+        let cursor_start = self.cursor;
+
+        self.skip_to_whitespace();
 
         &self.buffer[cursor_start..self.cursor]
     }
 
-    fn read_triangle(&mut self) -> Result<(), ()> {
+    fn read_triangle(&mut self) -> StlResult<()> {
         self.skip_whitespace();
         self.expect_bytes(b"facet")?;
         self.skip_whitespace();
         self.expect_bytes(b"normal")?;
         self.skip_whitespace();
 
-        self.read_normal();
+        let normal = self.read_normal()?;
 
         self.skip_whitespace();
         self.expect_bytes(b"outer")?;
@@ -101,8 +452,32 @@ impl<'a> AsciiParser<'a> {
         self.expect_bytes(b"loop")?;
         self.skip_whitespace();
 
-        for _ in 0..3 {
-            self.read_vertex()?;
+        let loop_start = self.cursor;
+
+        let first = self.read_vertex()?;
+        let mut prev = self.read_vertex()?;
+        let next = self.read_vertex()?;
+
+        self.push_triangle(normal, [first, prev, next])?;
+        prev = next;
+
+        let mut num_of_vertices = 3;
+
+        // split polygons into a fan of triangles sharing the first vertex
+        while !self.options.strict && self.next_is_keyword(b"vertex") {
+            let next = self.read_vertex()?;
+
+            self.push_triangle(normal, [first, prev, next])?;
+            prev = next;
+
+            num_of_vertices += 1;
+        }
+
+        if num_of_vertices > 3 {
+            self.warn(ParseWarning::Polygon {
+                offset: loop_start,
+                vertices: num_of_vertices,
+            });
         }
 
         self.expect_bytes(b"endloop")?;
@@ -114,44 +489,74 @@ impl<'a> AsciiParser<'a> {
         Ok(())
     }
 
-    fn read_normal(&mut self) {
-        let i = self.read_float();
-        let j = self.read_float();
-        let k = self.read_float();
+    fn push_triangle(&mut self, normal: Normal, vertices: [[f32; 3]; 3]) -> StlResult<()> {
+        self.num_of_facets += 1;
+        self.options.check_facet_count(self.num_of_facets)?;
+
+        if self.normals.len() == self.normals.capacity() {
+            self.grow();
+        }
 
-        self.normals.push(Normal { i, j, k });
+        self.normals.push(normal);
+
+        for vertex in &vertices {
+            self.vertices.extend_from_slice(vertex);
+        }
+
+        Ok(())
     }
 
-    fn read_float(&mut self) -> f32 {
-        let cursor_start = self.cursor;
+    /// Make room for more facets, doubling the capacity as `Vec` would but
+    /// never beyond the facets the limits still allow
+    fn grow(&mut self) {
+        let mut additional = self.normals.len().max(4);
 
-        while !self.next_is_whitespace() {
-            self.cursor += 1;
+        if let Some(budget) = self.options.facet_budget() {
+            // `num_of_facets` includes the facet about to be pushed
+            additional = additional.min(budget + 1 - self.num_of_facets);
         }
 
-        let float = fast_float::parse(&self.buffer[cursor_start..self.cursor]).unwrap();
+        self.normals.reserve_exact(additional);
+        self.vertices.reserve_exact(additional * 9);
+    }
 
-        self.skip_whitespace();
+    fn read_normal(&mut self) -> Result<Normal, ParseError> {
+        let i = self.read_float()?;
+        let j = self.read_float()?;
+        let k = self.read_float()?;
 
-        float
+        Ok(Normal { i, j, k })
     }
 
-    fn read_vertex(&mut self) -> Result<(), ()> {
-        self.expect_bytes(b"vertex")?;
-        for _ in 0..3 {
-            self.skip_whitespace();
-            let cursor_start = self.cursor;
+    fn read_float(&mut self) -> Result<f32, ParseError> {
+        if self.cursor >= self.buffer.len() {
+            return Err(self.eof_error());
+        }
 
-            while !self.next_is_whitespace() {
-                self.cursor += 1;
-            }
+        let cursor_start = self.cursor;
 
-            self.vertices
-                .push(fast_float::parse(&self.buffer[cursor_start..self.cursor]).unwrap());
+        self.skip_to_whitespace();
 
-            self.skip_whitespace();
-        }
+        let token = &self.buffer[cursor_start..self.cursor];
 
-        Ok(())
+        let float = fast_float::parse(token).map_err(|_| {
+            self.error(
+                ParseErrorKind::InvalidFloat {
+                    found: String::from_utf8_lossy(token).into_owned(),
+                },
+                cursor_start,
+            )
+        })?;
+
+        self.skip_whitespace();
+
+        Ok(float)
+    }
+
+    fn read_vertex(&mut self) -> Result<[f32; 3], ParseError> {
+        self.expect_bytes(b"vertex")?;
+        self.skip_whitespace();
+
+        Ok([self.read_float()?, self.read_float()?, self.read_float()?])
     }
 }
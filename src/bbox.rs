@@ -54,3 +54,9 @@ impl BoundingBox {
         self.max > p && self.min < p
     }
 }
+
+impl Default for BoundingBox {
+    fn default() -> Self {
+        Self::new()
+    }
+}
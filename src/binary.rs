@@ -1,4 +1,163 @@
-use crate::{Normal, StlFile};
+use crate::{
+    error::{ParseError, ParseErrorKind},
+    Normal, ParseOptions, ParseWarning, Point, StlFile, StlResult, Triangle,
+};
+
+/// Size of the header, including the facet count
+pub(crate) const HEADER_LEN: usize = 84;
+
+/// Size of a single facet: a normal, three vertices and the attribute byte count
+pub(crate) const FACET_LEN: usize = 50;
+
+/// Read the facet count from the header, checking that the buffer is long
+/// enough to contain that many facets
+pub(crate) fn read_facet_count(buffer: &[u8]) -> Result<u32, ParseError> {
+    if buffer.len() < HEADER_LEN {
+        return Err(ParseError::new(
+            ParseErrorKind::TruncatedHeader,
+            buffer.len(),
+        ));
+    }
+
+    let num_of_facets = declared_facet_count(buffer);
+
+    let body_len = buffer.len() - HEADER_LEN;
+    let available = body_len / FACET_LEN;
+
+    if available < num_of_facets as usize {
+        return Err(if available * FACET_LEN == body_len {
+            ParseError::new(
+                ParseErrorKind::FacetCountMismatch {
+                    expected: num_of_facets,
+                    found: available as u32,
+                },
+                buffer.len(),
+            )
+        } else {
+            ParseError::new(
+                ParseErrorKind::TruncatedFacet {
+                    facet: available as u32,
+                },
+                HEADER_LEN + available * FACET_LEN,
+            )
+        });
+    }
+
+    Ok(num_of_facets)
+}
+
+/// The facet count stored in the header, which must be at least `HEADER_LEN` long
+fn declared_facet_count(buffer: &[u8]) -> u32 {
+    u32::from_le_bytes([buffer[80], buffer[81], buffer[82], buffer[83]])
+}
+
+/// Decode a single facet record of `FACET_LEN` bytes
+pub(crate) fn decode_facet(record: &[u8]) -> (Triangle, u16) {
+    debug_assert_eq!(record.len(), FACET_LEN);
+
+    let f = |idx: usize| {
+        f32::from_le_bytes([
+            record[idx],
+            record[idx + 1],
+            record[idx + 2],
+            record[idx + 3],
+        ])
+    };
+
+    let triangle = Triangle {
+        normal: Normal {
+            i: f(0),
+            j: f(4),
+            k: f(8),
+        },
+        v0: Point {
+            x: f(12),
+            y: f(16),
+            z: f(20),
+        },
+        v1: Point {
+            x: f(24),
+            y: f(28),
+            z: f(32),
+        },
+        v2: Point {
+            x: f(36),
+            y: f(40),
+            z: f(44),
+        },
+    };
+
+    (triangle, u16::from_le_bytes([record[48], record[49]]))
+}
+
+/// Decode fixed-size chunks of facets on the rayon thread pool
+///
+/// `num_of_facets` must come from [`read_facet_count`], which checks that
+/// every facet is present, so this cannot fail.
+#[cfg(feature = "rayon")]
+pub(crate) fn par_parse(buffer: &[u8], num_of_facets: u32) -> StlFile {
+    use rayon::prelude::*;
+
+    /// Number of facets decoded by each task
+    const CHUNK_LEN: usize = 16 * 1024;
+
+    let num_of_facets = num_of_facets as usize;
+    let records = &buffer[HEADER_LEN..(HEADER_LEN + num_of_facets * FACET_LEN)];
+
+    let mut normals = vec![
+        Normal {
+            i: 0.0,
+            j: 0.0,
+            k: 0.0
+        };
+        num_of_facets
+    ];
+    let mut vertices = vec![0.0; num_of_facets * 9];
+    let mut attributes = vec![0; num_of_facets];
+
+    normals
+        .par_chunks_mut(CHUNK_LEN)
+        .zip(vertices.par_chunks_mut(CHUNK_LEN * 9))
+        .zip(attributes.par_chunks_mut(CHUNK_LEN))
+        .zip(records.par_chunks(CHUNK_LEN * FACET_LEN))
+        .for_each(|(((normals, vertices), attributes), records)| {
+            let facets = normals
+                .iter_mut()
+                .zip(vertices.chunks_exact_mut(9))
+                .zip(attributes.iter_mut())
+                .zip(records.chunks_exact(FACET_LEN));
+
+            for (((normal, vertices), attribute), record) in facets {
+                let (triangle, attr) = decode_facet(record);
+
+                *normal = triangle.normal;
+                *attribute = attr;
+
+                vertices.copy_from_slice(&[
+                    triangle.v0.x,
+                    triangle.v0.y,
+                    triangle.v0.z,
+                    triangle.v1.x,
+                    triangle.v1.y,
+                    triangle.v1.z,
+                    triangle.v2.x,
+                    triangle.v2.y,
+                    triangle.v2.z,
+                ]);
+            }
+        });
+
+    let mut header = [0; 80];
+    header.copy_from_slice(&buffer[..80]);
+
+    StlFile {
+        normals,
+        vertices,
+        attributes,
+        header,
+        name: String::new(),
+    }
+}
 
 #[derive(Debug)]
 pub struct BinaryParser<'a> {
@@ -6,52 +165,90 @@ pub struct BinaryParser<'a> {
     cursor: usize,
     normals: Vec<Normal>,
     vertices: Vec<f32>,
+    attributes: Vec<u16>,
     num_of_facets: u32,
     header: &'a [u8],
+    warnings: Vec<ParseWarning>,
 }
 
 impl<'a> BinaryParser<'a> {
-    pub fn new(buffer: &'a [u8]) -> Result<Self, ()> {
-        if buffer.len() < 80 {
-            return Err(());
+    /// Validate the header
+    ///
+    /// In lenient mode, a facet count larger than the number of facets present
+    /// is reported as a warning and only the facets present are parsed.
+    pub fn new(buffer: &'a [u8], options: ParseOptions) -> StlResult<Self> {
+        options.check_input_len(buffer.len())?;
+
+        let mut warnings = Vec::new();
+
+        let num_of_facets = match read_facet_count(buffer) {
+            Ok(num_of_facets) => num_of_facets,
+            Err(_) if !options.strict && buffer.len() >= HEADER_LEN => {
+                let found = ((buffer.len() - HEADER_LEN) / FACET_LEN) as u32;
+
+                warnings.push(ParseWarning::FacetCountMismatch {
+                    expected: declared_facet_count(buffer),
+                    found,
+                });
+
+                found
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        options.check_facet_count(num_of_facets as usize)?;
+
+        let end = HEADER_LEN + num_of_facets as usize * FACET_LEN;
+
+        if buffer.len() > end {
+            warnings.push(ParseWarning::TrailingBytes {
+                offset: end,
+                len: buffer.len() - end,
+            });
         }
 
         let mut bin_parser = Self {
             buffer,
             cursor: 0,
-            num_of_facets: 0,
-            normals: Vec::new(),
-            vertices: Vec::new(),
+            num_of_facets,
+            normals: Vec::with_capacity(num_of_facets as usize),
+            vertices: Vec::with_capacity(num_of_facets as usize * 3 * 3),
+            attributes: Vec::with_capacity(num_of_facets as usize),
             header: &[],
+            warnings,
         };
 
-        let header = bin_parser.read_header();
-        let num_of_facets = bin_parser.read_u32_le();
-
-        bin_parser.header = header;
-        bin_parser.num_of_facets = num_of_facets;
-        bin_parser.normals.reserve(num_of_facets as usize * 3);
-        bin_parser.vertices.reserve(num_of_facets as usize * 3 * 3);
+        bin_parser.header = bin_parser.read_header();
+        bin_parser.read_u32_le()?;
 
         Ok(bin_parser)
     }
 
-    pub fn parse(mut self) -> StlFile {
+    pub fn parse(mut self) -> Result<(StlFile, Vec<ParseWarning>), ParseError> {
         for _ in 0..self.num_of_facets {
-            self.read_normal();
+            self.read_normal()?;
 
-            self.read_vertex();
-            self.read_vertex();
-            self.read_vertex();
+            self.read_vertex()?;
+            self.read_vertex()?;
+            self.read_vertex()?;
 
-            // skip 2-byte short after triangle
-            self.cursor += 2;
+            let attribute = self.read_bytes(2)?;
+            self.attributes
+                .push(u16::from_le_bytes([attribute[0], attribute[1]]));
         }
 
-        StlFile {
+        let mut header = [0; 80];
+        header.copy_from_slice(self.header);
+
+        let file = StlFile {
             normals: self.normals,
             vertices: self.vertices,
-        }
+            attributes: self.attributes,
+            header,
+            name: String::new(),
+        };
+
+        Ok((file, self.warnings))
     }
 
     fn read_header(&mut self) -> &'a [u8] {
@@ -59,47 +256,53 @@ impl<'a> BinaryParser<'a> {
         &self.buffer[..80]
     }
 
-    fn read_u32_le(&mut self) -> u32 {
-        self.cursor += 4;
+    /// Consume `len` bytes, failing if the buffer ends before then
+    fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], ParseError> {
+        match self.buffer.get(self.cursor..(self.cursor + len)) {
+            Some(bytes) => {
+                self.cursor += len;
+                Ok(bytes)
+            }
+            None => Err(ParseError::new(
+                ParseErrorKind::TruncatedFacet {
+                    facet: self.normals.len() as u32,
+                },
+                self.cursor,
+            )),
+        }
+    }
 
-        debug_assert!(self.cursor <= self.buffer.len());
+    fn read_u32_le(&mut self) -> Result<u32, ParseError> {
+        let b = self.read_bytes(4)?;
 
-        u32::from_le_bytes([
-            self.buffer[self.cursor - 4],
-            self.buffer[self.cursor - 3],
-            self.buffer[self.cursor - 2],
-            self.buffer[self.cursor - 1],
-        ])
+        Ok(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
     }
 
-    fn read_f32_le(&mut self) -> f32 {
-        self.cursor += 4;
-
-        debug_assert!(self.cursor <= self.buffer.len());
+    fn read_f32_le(&mut self) -> Result<f32, ParseError> {
+        let b = self.read_bytes(4)?;
 
-        f32::from_le_bytes([
-            self.buffer[self.cursor - 4],
-            self.buffer[self.cursor - 3],
-            self.buffer[self.cursor - 2],
-            self.buffer[self.cursor - 1],
-        ])
+        Ok(f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
     }
 
-    fn read_normal(&mut self) {
-        let i = self.read_f32_le();
-        let j = self.read_f32_le();
-        let k = self.read_f32_le();
+    fn read_normal(&mut self) -> Result<(), ParseError> {
+        let i = self.read_f32_le()?;
+        let j = self.read_f32_le()?;
+        let k = self.read_f32_le()?;
 
         self.normals.push(Normal { i, j, k });
+
+        Ok(())
     }
 
-    fn read_vertex(&mut self) {
-        let x = self.read_f32_le();
-        let y = self.read_f32_le();
-        let z = self.read_f32_le();
+    fn read_vertex(&mut self) -> Result<(), ParseError> {
+        let x = self.read_f32_le()?;
+        let y = self.read_f32_le()?;
+        let z = self.read_f32_le()?;
 
         self.vertices.push(x);
         self.vertices.push(y);
         self.vertices.push(z);
+
+        Ok(())
     }
 }
@@ -0,0 +1,114 @@
+use std::collections::HashSet;
+
+use crate::{math::Vec3, IndexBuffer, Triangle};
+
+/// The number of facets removed by [`StlFile::remove_degenerate`] or
+/// [`StlFile::remove_duplicate_facets`], by reason
+///
+/// [`StlFile::remove_degenerate`]: crate::StlFile::remove_degenerate
+/// [`StlFile::remove_duplicate_facets`]: crate::StlFile::remove_duplicate_facets
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CleanupSummary {
+    /// Facets with two or more vertices which compare equal
+    pub repeated_vertices: usize,
+
+    /// Facets with distinct vertices, but an area no larger than the threshold
+    pub zero_area: usize,
+
+    /// Facets with the same vertices and winding as an earlier facet
+    pub duplicates: usize,
+
+    /// Facets with the same vertices as an earlier facet but the opposite
+    /// winding, so that the two lie back to back
+    pub reversed_duplicates: usize,
+}
+
+impl CleanupSummary {
+    /// The number of facets removed for any reason
+    pub fn total(&self) -> usize {
+        self.repeated_vertices + self.zero_area + self.duplicates + self.reversed_duplicates
+    }
+}
+
+/// Whether to keep each facet, dropping those with repeated vertices or an
+/// area of at most `area_eps`
+///
+/// Facets with NaN or infinite coordinates are always kept, as `Point`
+/// equality would treat NaN as zero.
+pub(crate) fn degenerate(
+    triangles: impl Iterator<Item = Triangle>,
+    area_eps: f32,
+) -> (Vec<bool>, CleanupSummary) {
+    let mut summary = CleanupSummary::default();
+
+    let keep = triangles
+        .map(|t| {
+            let [v0, v1, v2] = [t.v0, t.v1, t.v2].map(Vec3::from);
+
+            if ![v0, v1, v2].iter().all(|v| v.is_finite()) {
+                return true;
+            }
+
+            if t.v0 == t.v1 || t.v1 == t.v2 || t.v2 == t.v0 {
+                summary.repeated_vertices += 1;
+                return false;
+            }
+
+            let area = (v1 - v0).cross(v2 - v0).length() / 2.0;
+
+            if area <= f64::from(area_eps) {
+                summary.zero_area += 1;
+                return false;
+            }
+
+            true
+        })
+        .collect();
+
+    (keep, summary)
+}
+
+/// Whether to keep each facet, dropping those which repeat the vertices of an
+/// earlier facet in either winding
+pub(crate) fn duplicates(triangles: impl Iterator<Item = Triangle>) -> (Vec<bool>, CleanupSummary) {
+    let index_buffer = IndexBuffer::from_points(triangles.flat_map(|t| [t.v0, t.v1, t.v2]));
+
+    let mut summary = CleanupSummary::default();
+    let mut seen = HashSet::new();
+
+    let keep = index_buffer
+        .indices()
+        .chunks_exact(3)
+        .map(|ids| {
+            let facet = rotate_to_min([ids[0], ids[1], ids[2]]);
+            let reversed = rotate_to_min([ids[0], ids[2], ids[1]]);
+
+            if seen.contains(&facet) {
+                summary.duplicates += 1;
+                false
+            } else if seen.contains(&reversed) {
+                summary.reversed_duplicates += 1;
+                false
+            } else {
+                seen.insert(facet);
+                true
+            }
+        })
+        .collect();
+
+    (keep, summary)
+}
+
+/// Rotate the vertex ids of a facet so that the lowest comes first, which
+/// keeps the winding but makes every rotation of the same facet equal
+fn rotate_to_min(ids: [u32; 3]) -> [u32; 3] {
+    let [a, b, c] = ids;
+
+    if a <= b && a <= c {
+        [a, b, c]
+    } else if b <= c {
+        [b, c, a]
+    } else {
+        [c, a, b]
+    }
+}
@@ -0,0 +1,130 @@
+/// An RGB color with 8 bits per channel
+///
+/// Facet colors are stored with only 5 bits per channel, so the low 3 bits of
+/// each channel are lost when encoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+/// The two conventions for storing a 15-bit color in a facet's attribute bytes
+///
+/// ```
+/// # use stl::{Color, ColorFormat};
+/// let red = Color { r: 255, g: 0, b: 0 };
+///
+/// let attribute = ColorFormat::VisCam.encode(Some(red));
+/// assert_eq!(attribute, 0b1_11111_00000_00000);
+/// assert_eq!(ColorFormat::VisCam.decode(attribute), Some(red));
+///
+/// let attribute = ColorFormat::Materialise.encode(Some(red));
+/// assert_eq!(attribute, 0b0_00000_00000_11111);
+/// assert_eq!(ColorFormat::Materialise.decode(attribute), Some(red));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColorFormat {
+    /// Used by VisCAM and SolidView. Blue occupies the lowest 5 bits, followed
+    /// by green and red. Bit 15 is set when the color is valid.
+    VisCam,
+
+    /// Used by Materialise Magics. Red occupies the lowest 5 bits, followed
+    /// by green and blue. Bit 15 is *cleared* when the color is valid; when it
+    /// is set, the facet uses the default color from the header.
+    Materialise,
+}
+
+const FLAG: u16 = 1 << 15;
+
+impl ColorFormat {
+    /// The color stored in a facet's attribute bytes, if any
+    pub fn decode(self, attribute: u16) -> Option<Color> {
+        let channel = |shift: u16| expand((attribute >> shift) as u8 & 0x1f);
+
+        match self {
+            Self::VisCam if attribute & FLAG != 0 => Some(Color {
+                r: channel(10),
+                g: channel(5),
+                b: channel(0),
+            }),
+            Self::Materialise if attribute & FLAG == 0 => Some(Color {
+                r: channel(0),
+                g: channel(5),
+                b: channel(10),
+            }),
+            _ => None,
+        }
+    }
+
+    /// The attribute bytes for a facet with the given color, or with no color
+    pub fn encode(self, color: Option<Color>) -> u16 {
+        let color = match color {
+            Some(color) => color,
+            None => {
+                return match self {
+                    Self::VisCam => 0,
+                    Self::Materialise => FLAG,
+                }
+            }
+        };
+
+        let r = u16::from(color.r >> 3);
+        let g = u16::from(color.g >> 3);
+        let b = u16::from(color.b >> 3);
+
+        match self {
+            Self::VisCam => FLAG | (r << 10) | (g << 5) | b,
+            Self::Materialise => (b << 10) | (g << 5) | r,
+        }
+    }
+}
+
+/// Scale a 5-bit channel to 8 bits, mapping 31 to 255
+fn expand(channel: u8) -> u8 {
+    (channel << 3) | (channel >> 2)
+}
+
+/// Material properties stored after `MATERIAL=` in a Materialise header
+///
+/// Each value is stored as RGBA.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Material {
+    pub diffuse: [u8; 4],
+    pub specular: [u8; 4],
+    pub ambient: [u8; 4],
+}
+
+pub(crate) const COLOR_KEY: &[u8] = b"COLOR=";
+pub(crate) const MATERIAL_KEY: &[u8] = b"MATERIAL=";
+
+/// Offsets at which keys are written into headers that do not already contain
+/// them. Both are placed at the end of the header, so that any leading text
+/// written by an exporter is preserved.
+pub(crate) const MATERIAL_SLOT: usize = 80 - 9 - 12;
+pub(crate) const COLOR_SLOT: usize = MATERIAL_SLOT - 6 - 4;
+
+/// The bytes following `key` in the header, if the key is present
+pub(crate) fn find_header_value<'a>(
+    header: &'a [u8; 80],
+    key: &[u8],
+    len: usize,
+) -> Option<&'a [u8]> {
+    header
+        .windows(key.len())
+        .position(|window| window == key)
+        .and_then(|idx| header.get((idx + key.len())..(idx + key.len() + len)))
+}
+
+/// Write `key` followed by `value` into the header, replacing the existing
+/// value if the key is already present and writing it at `slot` otherwise
+pub(crate) fn set_header_value(header: &mut [u8; 80], key: &[u8], value: &[u8], slot: usize) {
+    let start = header
+        .windows(key.len())
+        .position(|window| window == key)
+        .filter(|idx| idx + key.len() + value.len() <= header.len())
+        .unwrap_or(slot);
+
+    header[start..(start + key.len())].copy_from_slice(key);
+    header[(start + key.len())..(start + key.len() + value.len())].copy_from_slice(value);
+}
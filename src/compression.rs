@@ -0,0 +1,79 @@
+use std::io::{self, Cursor};
+
+use flate2::read::MultiGzDecoder;
+use zip::ZipArchive;
+
+use crate::{ParseOptions, StlResult};
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+
+/// The container an STL was uploaded in, detected from its magic bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Compression {
+    Gzip,
+    Zip,
+    None,
+}
+
+impl Compression {
+    pub fn detect(buffer: &[u8]) -> Self {
+        if buffer.starts_with(GZIP_MAGIC) {
+            Self::Gzip
+        } else if buffer.starts_with(ZIP_MAGIC) {
+            Self::Zip
+        } else {
+            Self::None
+        }
+    }
+}
+
+/// Decompress a gzip stream, enforcing `options.max_input_len` on the
+/// decompressed size
+pub(crate) fn gunzip(buffer: &[u8], options: &ParseOptions) -> StlResult<Vec<u8>> {
+    options.read_to_end(MultiGzDecoder::new(buffer))
+}
+
+/// Decompress each `.stl` file in a zip archive in the order they are stored,
+/// passing its name and contents to `f` before reading the next
+///
+/// `options.max_input_len` limits the decompressed size of all entries
+/// together, so that many small entries cannot add up to more.
+pub(crate) fn unzip<T>(
+    buffer: &[u8],
+    options: &ParseOptions,
+    mut f: impl FnMut(String, Vec<u8>) -> StlResult<T>,
+) -> StlResult<Vec<T>> {
+    let mut archive = ZipArchive::new(Cursor::new(buffer)).map_err(io::Error::from)?;
+    let mut entries = Vec::new();
+    let mut remaining = options.max_input_len;
+
+    for idx in 0..archive.len() {
+        let entry = archive.by_index(idx).map_err(io::Error::from)?;
+
+        if entry.is_dir() || !entry.name().to_ascii_lowercase().ends_with(".stl") {
+            continue;
+        }
+
+        let name = entry.name().to_owned();
+        let contents = ParseOptions {
+            max_input_len: remaining,
+            ..*options
+        }
+        .read_to_end(entry)
+        .map_err(|err| options.restore_limit(err))?;
+
+        remaining = remaining.map(|len| len - contents.len());
+        entries.push(f(name, contents)?);
+    }
+
+    if entries.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "zip archive contains no .stl files",
+        )
+        .into());
+    }
+
+    Ok(entries)
+}
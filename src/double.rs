@@ -0,0 +1,223 @@
+use crate::{Normal, Point, StlFile, Triangle};
+
+/// A double precision [`Point`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Point64 {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl Point64 {
+    /// Round each coordinate to the nearest `f32`
+    pub fn to_f32(self) -> Point {
+        Point {
+            x: self.x as f32,
+            y: self.y as f32,
+            z: self.z as f32,
+        }
+    }
+}
+
+impl From<Point> for Point64 {
+    fn from(p: Point) -> Self {
+        Self {
+            x: f64::from(p.x),
+            y: f64::from(p.y),
+            z: f64::from(p.z),
+        }
+    }
+}
+
+/// A double precision [`Normal`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Normal64 {
+    pub i: f64,
+    pub j: f64,
+    pub k: f64,
+}
+
+impl Normal64 {
+    /// Round each component to the nearest `f32`
+    pub fn to_f32(self) -> Normal {
+        Normal {
+            i: self.i as f32,
+            j: self.j as f32,
+            k: self.k as f32,
+        }
+    }
+}
+
+impl From<Normal> for Normal64 {
+    fn from(n: Normal) -> Self {
+        Self {
+            i: f64::from(n.i),
+            j: f64::from(n.j),
+            k: f64::from(n.k),
+        }
+    }
+}
+
+/// A double precision [`Triangle`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Triangle64 {
+    pub normal: Normal64,
+    pub v0: Point64,
+    pub v1: Point64,
+    pub v2: Point64,
+}
+
+impl Triangle64 {
+    /// Round each coordinate to the nearest `f32`
+    pub fn to_f32(self) -> Triangle {
+        Triangle {
+            normal: self.normal.to_f32(),
+            v0: self.v0.to_f32(),
+            v1: self.v1.to_f32(),
+            v2: self.v2.to_f32(),
+        }
+    }
+}
+
+impl From<Triangle> for Triangle64 {
+    fn from(t: Triangle) -> Self {
+        Self {
+            normal: t.normal.into(),
+            v0: t.v0.into(),
+            v1: t.v1.into(),
+            v2: t.v2.into(),
+        }
+    }
+}
+
+/// A double precision [`StlFile`]
+///
+/// STL stores every coordinate as an `f32`, so precision is lost when large
+/// coordinates are transformed in place. Converting to an `StlFile64` first is
+/// lossless, and the transformed mesh can then be narrowed once with
+/// [`StlFile64::to_f32`] before writing.
+///
+/// ```
+/// # use stl::{StlFile, StlFile64};
+/// let stl = StlFile::parse(b"solid foo
+/// facet normal 0 0 1 outer loop vertex 0 0 0 vertex 1 0 0 vertex 0 1 0 endloop endfacet
+/// endsolid foo").unwrap();
+///
+/// let mut stl64 = StlFile64::from(&stl);
+///
+/// // move to site coordinates and back
+/// for x in stl64.vertex_buffer_mut().iter_mut().step_by(3) {
+///     *x += 123_456_789.0;
+/// }
+/// for x in stl64.vertex_buffer_mut().iter_mut().step_by(3) {
+///     *x -= 123_456_789.0;
+/// }
+///
+/// assert_eq!(stl64.to_f32(), stl);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct StlFile64 {
+    normals: Vec<Normal64>,
+    vertices: Vec<f64>,
+    attributes: Vec<u16>,
+    header: [u8; 80],
+    name: String,
+}
+
+impl StlFile64 {
+    /// Round every coordinate to the nearest `f32`, producing a file which can
+    /// be written
+    pub fn to_f32(&self) -> StlFile {
+        StlFile {
+            normals: self.normals.iter().map(|n| n.to_f32()).collect(),
+            vertices: self.vertices.iter().map(|&v| v as f32).collect(),
+            attributes: self.attributes.clone(),
+            header: self.header,
+            name: self.name.clone(),
+        }
+    }
+
+    pub fn header(&self) -> &[u8; 80] {
+        &self.header
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn attributes(&self) -> &[u16] {
+        &self.attributes
+    }
+
+    /// Number of facets
+    pub fn facet_count(&self) -> u32 {
+        self.normals.len() as u32
+    }
+
+    /// Contiguous slice of memory containing all vertices
+    pub fn vertex_buffer(&self) -> &[f64] {
+        &self.vertices
+    }
+
+    pub fn vertex_buffer_mut(&mut self) -> &mut [f64] {
+        &mut self.vertices
+    }
+
+    pub fn normals(&self) -> &[Normal64] {
+        &self.normals
+    }
+
+    pub fn normals_mut(&mut self) -> &mut [Normal64] {
+        &mut self.normals
+    }
+
+    pub fn vertices<'a>(&'a self) -> impl Iterator<Item = Point64> + 'a {
+        self.vertices.chunks_exact(3).map(|chunk| Point64 {
+            x: chunk[0],
+            y: chunk[1],
+            z: chunk[2],
+        })
+    }
+
+    pub fn triangles<'a>(&'a self) -> impl Iterator<Item = Triangle64> + 'a {
+        self.vertices
+            .chunks_exact(9)
+            .zip(&self.normals)
+            .map(|(v, &normal)| Triangle64 {
+                normal,
+                v0: Point64 {
+                    x: v[0],
+                    y: v[1],
+                    z: v[2],
+                },
+                v1: Point64 {
+                    x: v[3],
+                    y: v[4],
+                    z: v[5],
+                },
+                v2: Point64 {
+                    x: v[6],
+                    y: v[7],
+                    z: v[8],
+                },
+            })
+    }
+}
+
+impl From<&StlFile> for StlFile64 {
+    fn from(file: &StlFile) -> Self {
+        Self {
+            normals: file.normals.iter().map(|&n| n.into()).collect(),
+            vertices: file.vertices.iter().map(|&v| f64::from(v)).collect(),
+            attributes: file.attributes.clone(),
+            header: file.header,
+            name: file.name.clone(),
+        }
+    }
+}
+
+impl From<StlFile> for StlFile64 {
+    fn from(file: StlFile) -> Self {
+        Self::from(&file)
+    }
+}
@@ -1,11 +1,32 @@
-use std::io;
+use std::{error::Error, fmt, io};
 
 pub type StlResult<T> = Result<T, StlError>;
 
 #[derive(Debug)]
 pub enum StlError {
     IoError(io::Error),
-    ParseError(()),
+    ParseError(ParseError),
+    LimitExceeded(LimitExceeded),
+}
+
+impl fmt::Display for StlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::IoError(err) => write!(f, "io error: {}", err),
+            Self::ParseError(err) => err.fmt(f),
+            Self::LimitExceeded(err) => err.fmt(f),
+        }
+    }
+}
+
+impl Error for StlError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::IoError(err) => Some(err),
+            Self::ParseError(err) => Some(err),
+            Self::LimitExceeded(err) => Some(err),
+        }
+    }
 }
 
 impl From<io::Error> for StlError {
@@ -14,8 +35,295 @@ impl From<io::Error> for StlError {
     }
 }
 
-impl From<()> for StlError {
-    fn from(_: ()) -> Self {
-        Self::ParseError(())
+impl From<ParseError> for StlError {
+    fn from(err: ParseError) -> Self {
+        Self::ParseError(err)
+    }
+}
+
+impl From<LimitExceeded> for StlError {
+    fn from(err: LimitExceeded) -> Self {
+        Self::LimitExceeded(err)
+    }
+}
+
+/// A resource limit set in [`ParseOptions`](crate::ParseOptions) was exceeded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitExceeded {
+    /// The input was longer than `limit` bytes
+    InputLen { limit: usize },
+
+    /// The input contained more than `limit` facets
+    FacetCount { limit: u32 },
+
+    /// The parsed mesh would occupy more than `limit` bytes
+    Allocation { limit: usize },
+}
+
+impl fmt::Display for LimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InputLen { limit } => write!(f, "input is longer than {} bytes", limit),
+            Self::FacetCount { limit } => write!(f, "input has more than {} facets", limit),
+            Self::Allocation { limit } => {
+                write!(f, "parsed mesh would be larger than {} bytes", limit)
+            }
+        }
+    }
+}
+
+impl Error for LimitExceeded {}
+
+/// The reason a buffer could not be parsed as STL
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseErrorKind {
+    /// A token was found where a different one was required
+    UnexpectedToken { found: String },
+
+    /// A specific keyword such as `facet` or `endloop` was required
+    ExpectedKeyword {
+        expected: &'static str,
+        found: String,
+    },
+
+    /// `solid` was not followed by a space and the name of the solid, as
+    /// strict parsing requires
+    MissingName,
+
+    /// A token could not be parsed as a floating point number
+    InvalidFloat { found: String },
+
+    /// The input ended before the closing `endsolid`
+    UnexpectedEof,
+
+    /// The input is too short to contain a binary header and facet count
+    TruncatedHeader,
+
+    /// The input ended in the middle of a facet
+    TruncatedFacet { facet: u32 },
+
+    /// The facet count in the binary header disagrees with the number of
+    /// facets actually present
+    FacetCountMismatch { expected: u32, found: u32 },
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedToken { found } => write!(f, "unexpected token `{}`", found),
+            Self::ExpectedKeyword { expected, found } => {
+                write!(f, "expected `{}`, found `{}`", expected, found)
+            }
+            Self::MissingName => write!(f, "expected `solid <name>`"),
+            Self::InvalidFloat { found } => write!(f, "invalid float `{}`", found),
+            Self::UnexpectedEof => write!(f, "unexpected end of input"),
+            Self::TruncatedHeader => write!(f, "binary header is truncated"),
+            Self::TruncatedFacet { facet } => write!(f, "facet {} is truncated", facet),
+            Self::FacetCountMismatch { expected, found } => write!(
+                f,
+                "header declares {} facets, but {} are present",
+                expected, found
+            ),
+        }
+    }
+}
+
+/// Human readable position of an error inside an ASCII STL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    /// 1-based line number
+    pub line: usize,
+
+    /// 1-based column, counted in bytes
+    pub column: usize,
+
+    /// The contents of the offending line
+    pub snippet: String,
+}
+
+impl Location {
+    /// Maximum number of bytes of the offending line kept in `snippet`
+    const MAX_SNIPPET_LEN: usize = 80;
+
+    pub(crate) fn from_offset(buffer: &[u8], offset: usize) -> Self {
+        let offset = offset.min(buffer.len());
+
+        let line_start = buffer[..offset]
+            .iter()
+            .rposition(|&b| b == b'\n')
+            .map_or(0, |idx| idx + 1);
+
+        let line_end = buffer[offset..]
+            .iter()
+            .position(|&b| b == b'\n' || b == b'\r')
+            .map_or(buffer.len(), |idx| offset + idx);
+
+        let line = buffer[..line_start].iter().filter(|&&b| b == b'\n').count() + 1;
+
+        let snippet_end = line_end.min(line_start + Self::MAX_SNIPPET_LEN);
+
+        Self {
+            line,
+            column: offset - line_start + 1,
+            snippet: String::from_utf8_lossy(&buffer[line_start..snippet_end])
+                .trim_end()
+                .to_owned(),
+        }
+    }
+}
+
+/// An error encountered while parsing, alongside where it occurred
+///
+/// ```
+/// # use stl::{StlError, StlFile};
+/// let err = StlFile::parse(b"solid foo\nfacet normal 0 0 x\n").unwrap_err();
+///
+/// if let StlError::ParseError(err) = err {
+///     let location = err.location().unwrap();
+///     assert_eq!((location.line, location.column), (2, 18));
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    kind: ParseErrorKind,
+    offset: usize,
+    location: Option<Location>,
+}
+
+impl ParseError {
+    pub(crate) fn new(kind: ParseErrorKind, offset: usize) -> Self {
+        Self {
+            kind,
+            offset,
+            location: None,
+        }
+    }
+
+    pub(crate) fn with_location(mut self, location: Location) -> Self {
+        self.location = Some(location);
+        self
+    }
+
+    pub fn kind(&self) -> &ParseErrorKind {
+        &self.kind
+    }
+
+    /// Byte offset into the input at which the error occurred
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Line and column of the error. Only available for ASCII input
+    pub fn location(&self) -> Option<&Location> {
+        self.location.as_ref()
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.location {
+            Some(Location {
+                line,
+                column,
+                snippet,
+            }) => write!(
+                f,
+                "{} at line {}, column {}: `{}`",
+                self.kind, line, column, snippet
+            ),
+            None => write!(f, "{} at byte offset {}", self.kind, self.offset),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// Input which was suspicious, but did not prevent parsing
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseWarning {
+    /// A keyword was not lowercase. Only the first occurrence is reported
+    KeywordCase { offset: usize },
+
+    /// `solid` was not followed by a name
+    MissingName { offset: usize },
+
+    /// A loop contained more than three vertices and was split into triangles
+    Polygon { offset: usize, vertices: usize },
+
+    /// Content following the last `endsolid` was ignored
+    TrailingContent { offset: usize },
+
+    /// The facet count in a binary header was larger than the number of
+    /// facets in the file. Only the facets present were parsed
+    FacetCountMismatch { expected: u32, found: u32 },
+
+    /// Bytes following the last facet of a binary file were ignored
+    TrailingBytes { offset: usize, len: usize },
+
+    /// A facet has a NaN or infinite coordinate in its normal or vertices
+    NonFinite { facet: u32 },
+
+    /// The stored normal of a facet points more than
+    /// [`ParseWarning::NORMAL_TOLERANCE`] degrees away from the normal implied
+    /// by its winding
+    NormalMismatch { facet: u32, angle: f32 },
+
+    /// The vertices of a facet are collinear or coincident
+    ZeroArea { facet: u32 },
+}
+
+impl ParseWarning {
+    /// Largest angle in degrees between a stored normal and the normal implied
+    /// by the winding of its facet before [`ParseWarning::NormalMismatch`] is
+    /// reported
+    pub const NORMAL_TOLERANCE: f32 = 45.0;
+
+    /// Whether only the first occurrence of this warning is worth reporting
+    pub(crate) fn is_reported_once(&self) -> bool {
+        matches!(self, Self::KeywordCase { .. })
+    }
+}
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::KeywordCase { offset } => {
+                write!(f, "keyword is not lowercase at byte offset {}", offset)
+            }
+            Self::MissingName { offset } => {
+                write!(f, "solid has no name at byte offset {}", offset)
+            }
+            Self::Polygon { offset, vertices } => write!(
+                f,
+                "loop with {} vertices was triangulated at byte offset {}",
+                vertices, offset
+            ),
+            Self::TrailingContent { offset } => {
+                write!(
+                    f,
+                    "ignored content after endsolid at byte offset {}",
+                    offset
+                )
+            }
+            Self::FacetCountMismatch { expected, found } => write!(
+                f,
+                "header declares {} facets, but only {} are present",
+                expected, found
+            ),
+            Self::TrailingBytes { offset, len } => write!(
+                f,
+                "ignored {} bytes after the last facet at byte offset {}",
+                len, offset
+            ),
+            Self::NonFinite { facet } => {
+                write!(f, "facet {} has a non-finite coordinate", facet)
+            }
+            Self::NormalMismatch { facet, angle } => write!(
+                f,
+                "normal of facet {} is {} degrees from its winding",
+                facet, angle
+            ),
+            Self::ZeroArea { facet } => write!(f, "facet {} has zero area", facet),
+        }
     }
 }
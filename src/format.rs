@@ -0,0 +1,135 @@
+/// The two encodings an STL file may be stored in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StlFormat {
+    Ascii,
+    Binary,
+}
+
+impl StlFormat {
+    /// Number of bytes searched for ASCII keywords when sniffing the format
+    const SNIFF_LEN: usize = 1024;
+
+    /// Guess the format of a buffer
+    ///
+    /// Binary files are allowed to begin with `solid`, so the prefix alone is
+    /// not enough. A buffer is considered ASCII when it begins with `solid` and
+    /// is followed by a `facet normal` or `endsolid` keyword within the first
+    /// kilobyte of text. Otherwise, a facet count in the binary header that
+    /// exactly accounts for the length of the buffer (`84 + 50 * n`) marks it
+    /// as binary. If neither check is conclusive, the `solid` prefix decides.
+    ///
+    /// ```
+    /// # use stl::StlFormat;
+    /// assert_eq!(StlFormat::detect(b"solid foo\nendsolid foo\n"), StlFormat::Ascii);
+    ///
+    /// let mut binary = b"solid exported by a CAD tool".to_vec();
+    /// binary.resize(84, 0);
+    /// assert_eq!(StlFormat::detect(&binary), StlFormat::Binary);
+    /// ```
+    pub fn detect(buffer: &[u8]) -> Self {
+        Self::detect_case(buffer, false)
+    }
+
+    /// Guess the format of a buffer as [`StlFormat::detect`] does, but
+    /// matching keywords in any case, as lenient parsing accepts them
+    pub(crate) fn detect_ignoring_case(buffer: &[u8]) -> Self {
+        Self::detect_case(buffer, true)
+    }
+
+    fn detect_case(buffer: &[u8], ignore_case: bool) -> Self {
+        let starts_with_solid = starts_with_solid(buffer, ignore_case);
+
+        if starts_with_solid && has_ascii_keywords(trim_start(buffer), ignore_case) {
+            return Self::Ascii;
+        }
+
+        if binary_length_matches(buffer) {
+            return Self::Binary;
+        }
+
+        if starts_with_solid {
+            Self::Ascii
+        } else {
+            Self::Binary
+        }
+    }
+
+    /// Guess the format of input from only its first bytes, as when streaming
+    ///
+    /// The binary length check is impossible without the whole input, so
+    /// anything lacking ASCII keywords near the start is assumed to be binary.
+    pub(crate) fn detect_prefix(head: &[u8]) -> Self {
+        let text = trim_start(head);
+
+        if starts_with_solid(text, false) && has_ascii_keywords(text, false) {
+            Self::Ascii
+        } else {
+            Self::Binary
+        }
+    }
+
+    /// The format to try if parsing as this one fails
+    pub(crate) fn other(self) -> Self {
+        match self {
+            Self::Ascii => Self::Binary,
+            Self::Binary => Self::Ascii,
+        }
+    }
+}
+
+fn trim_start(buffer: &[u8]) -> &[u8] {
+    let start = buffer
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .unwrap_or(buffer.len());
+
+    &buffer[start..]
+}
+
+/// Whether the buffer begins with the `solid` keyword after any whitespace
+pub(crate) fn starts_with_solid(buffer: &[u8], ignore_case: bool) -> bool {
+    match trim_start(buffer).get(..b"solid".len()) {
+        Some(word) => word == b"solid" || (ignore_case && word.eq_ignore_ascii_case(b"solid")),
+        None => false,
+    }
+}
+
+/// Whether the header's facet count exactly accounts for the buffer's length
+fn binary_length_matches(buffer: &[u8]) -> bool {
+    if buffer.len() < 84 {
+        return false;
+    }
+
+    let num_of_facets = u32::from_le_bytes([buffer[80], buffer[81], buffer[82], buffer[83]]);
+
+    (num_of_facets as u64) * 50 + 84 == buffer.len() as u64
+}
+
+/// Whether the text following `solid` looks like the start of an ASCII STL
+fn has_ascii_keywords(text: &[u8], ignore_case: bool) -> bool {
+    let text = &text[..text.len().min(StlFormat::SNIFF_LEN)];
+
+    // binary headers are frequently padded with nul bytes; text never is
+    if text.contains(&0) {
+        return false;
+    }
+
+    let mut words = text[b"solid".len()..]
+        .split(u8::is_ascii_whitespace)
+        .filter(|word| !word.is_empty())
+        .peekable();
+
+    let is = |word: &[u8], keyword: &[u8]| {
+        word == keyword || (ignore_case && word.eq_ignore_ascii_case(keyword))
+    };
+
+    while let Some(word) = words.next() {
+        if is(word, b"endsolid")
+            || (is(word, b"facet") && matches!(words.peek(), Some(next) if is(next, b"normal")))
+        {
+            return true;
+        }
+    }
+
+    false
+}
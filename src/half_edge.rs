@@ -0,0 +1,307 @@
+use std::collections::HashMap;
+
+use crate::{IndexBuffer, Normal, Point, StlFile, Triangle};
+
+/// A mesh with explicit connectivity between its facets, edges and vertices
+///
+/// Vertices are welded wherever their [`Point`]s compare equal. Each facet
+/// owns three half-edges, following its winding, so half-edge `3 * f + i`
+/// starts at vertex `i` of facet `f`. Two half-edges are twins when they join
+/// the same vertices in opposite directions and no other facet uses that
+/// edge. Half-edges without a twin lie on a boundary, or on an edge which is
+/// non-manifold or wound inconsistently.
+///
+/// ```
+/// # use stl::{HalfEdgeMesh, StlFile};
+/// let stl = StlFile::parse(b"solid square
+/// facet normal 0 0 1 outer loop vertex 0 0 0 vertex 1 0 0 vertex 1 1 0 endloop endfacet
+/// facet normal 0 0 1 outer loop vertex 0 0 0 vertex 1 1 0 vertex 0 1 0 endloop endfacet
+/// endsolid square").unwrap();
+///
+/// let mesh = HalfEdgeMesh::new(&stl);
+///
+/// assert_eq!(mesh.vertex_count(), 4);
+/// assert_eq!(mesh.facet_neighbors(0).collect::<Vec<_>>(), vec![1]);
+/// assert_eq!(mesh.boundary_loops(), vec![vec![0, 1, 2, 3]]);
+/// assert_eq!(mesh.to_stl_file().triangles().count(), 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct HalfEdgeMesh {
+    points: Vec<Point>,
+
+    /// The vertex each half-edge starts from
+    origins: Vec<usize>,
+    twins: Vec<Option<usize>>,
+
+    /// The half-edges leaving each vertex, stored contiguously with
+    /// `outgoing_offsets[v]..outgoing_offsets[v + 1]` indexing those of `v`
+    outgoing: Vec<usize>,
+    outgoing_offsets: Vec<usize>,
+
+    normals: Vec<Normal>,
+    attributes: Vec<u16>,
+}
+
+impl HalfEdgeMesh {
+    pub fn new(stl: &StlFile) -> Self {
+        let index_buffer = IndexBuffer::from_points(stl.vertices());
+
+        let points = index_buffer
+            .vertices()
+            .chunks_exact(3)
+            .map(|p| Point {
+                x: p[0],
+                y: p[1],
+                z: p[2],
+            })
+            .collect::<Vec<_>>();
+
+        let origins = index_buffer
+            .indices()
+            .iter()
+            .map(|&idx| idx as usize)
+            .collect::<Vec<_>>();
+
+        let mut mesh = Self {
+            twins: vec![None; origins.len()],
+            outgoing: Vec::new(),
+            outgoing_offsets: Vec::new(),
+            points,
+            origins,
+            normals: stl.normals().to_vec(),
+            attributes: stl.attributes().to_vec(),
+        };
+
+        mesh.link_twins();
+        mesh.index_outgoing();
+
+        mesh
+    }
+
+    fn link_twins(&mut self) {
+        let mut edges: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+
+        for half_edge in 0..self.origins.len() {
+            let (a, b) = (self.origin(half_edge), self.target(half_edge));
+
+            if a != b {
+                edges
+                    .entry((a.min(b), a.max(b)))
+                    .or_default()
+                    .push(half_edge);
+            }
+        }
+
+        for half_edges in edges.values() {
+            if let [h0, h1] = *half_edges.as_slice() {
+                if self.origin(h0) == self.target(h1) {
+                    self.twins[h0] = Some(h1);
+                    self.twins[h1] = Some(h0);
+                }
+            }
+        }
+    }
+
+    fn index_outgoing(&mut self) {
+        let mut offsets = vec![0; self.points.len() + 1];
+
+        for &origin in &self.origins {
+            offsets[origin + 1] += 1;
+        }
+
+        for idx in 1..offsets.len() {
+            offsets[idx] += offsets[idx - 1];
+        }
+
+        let mut next = offsets.clone();
+        let mut outgoing = vec![0; self.origins.len()];
+
+        for (half_edge, &origin) in self.origins.iter().enumerate() {
+            outgoing[next[origin]] = half_edge;
+            next[origin] += 1;
+        }
+
+        self.outgoing = outgoing;
+        self.outgoing_offsets = offsets;
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn facet_count(&self) -> usize {
+        self.normals.len()
+    }
+
+    pub fn half_edge_count(&self) -> usize {
+        self.origins.len()
+    }
+
+    /// The position of a vertex
+    ///
+    /// # Panics
+    ///
+    /// Panics if `vertex` is out of bounds
+    pub fn point(&self, vertex: usize) -> Point {
+        self.points[vertex]
+    }
+
+    /// The vertices of a facet in winding order
+    pub fn facet_vertices(&self, facet: usize) -> [usize; 3] {
+        let [h0, h1, h2] = self.facet_half_edges(facet);
+
+        [self.origins[h0], self.origins[h1], self.origins[h2]]
+    }
+
+    /// The half-edges of a facet in winding order
+    pub fn facet_half_edges(&self, facet: usize) -> [usize; 3] {
+        [3 * facet, 3 * facet + 1, 3 * facet + 2]
+    }
+
+    /// The facet a half-edge belongs to
+    pub fn facet(&self, half_edge: usize) -> usize {
+        half_edge / 3
+    }
+
+    /// The vertex a half-edge starts from
+    pub fn origin(&self, half_edge: usize) -> usize {
+        self.origins[half_edge]
+    }
+
+    /// The vertex a half-edge points to
+    pub fn target(&self, half_edge: usize) -> usize {
+        self.origins[self.next(half_edge)]
+    }
+
+    /// The following half-edge around the same facet
+    pub fn next(&self, half_edge: usize) -> usize {
+        if half_edge % 3 == 2 {
+            half_edge - 2
+        } else {
+            half_edge + 1
+        }
+    }
+
+    /// The preceding half-edge around the same facet
+    pub fn prev(&self, half_edge: usize) -> usize {
+        match half_edge % 3 {
+            0 => half_edge + 2,
+            _ => half_edge - 1,
+        }
+    }
+
+    /// The half-edge joining the same vertices in the opposite direction in
+    /// the neighboring facet, or `None` on a boundary
+    pub fn twin(&self, half_edge: usize) -> Option<usize> {
+        self.twins[half_edge]
+    }
+
+    /// The half-edges leaving a vertex
+    pub fn outgoing(&self, vertex: usize) -> &[usize] {
+        &self.outgoing[self.outgoing_offsets[vertex]..self.outgoing_offsets[vertex + 1]]
+    }
+
+    /// Facets sharing an edge with `facet`
+    pub fn facet_neighbors(&self, facet: usize) -> impl Iterator<Item = usize> + '_ {
+        IntoIterator::into_iter(self.facet_half_edges(facet))
+            .filter_map(move |half_edge| self.twin(half_edge))
+            .map(move |twin| self.facet(twin))
+    }
+
+    /// Vertices joined to `vertex` by an edge, in the order first encountered
+    pub fn vertex_one_ring(&self, vertex: usize) -> Vec<usize> {
+        let mut ring = Vec::new();
+
+        for &half_edge in self.outgoing(vertex) {
+            for neighbor in [self.target(half_edge), self.origin(self.prev(half_edge))].iter() {
+                if *neighbor != vertex && !ring.contains(neighbor) {
+                    ring.push(*neighbor);
+                }
+            }
+        }
+
+        ring
+    }
+
+    /// Half-edges without a twin
+    pub fn boundary_half_edges(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.origins.len()).filter(move |&half_edge| {
+            self.twin(half_edge).is_none() && self.origin(half_edge) != self.target(half_edge)
+        })
+    }
+
+    /// The vertices around each hole, following the winding of the
+    /// surrounding facets
+    ///
+    /// Loops are ordered by their first boundary half-edge, and each starts at
+    /// the origin of that half-edge. Where several boundaries meet at a
+    /// vertex, they are followed in order of their half-edges.
+    pub fn boundary_loops(&self) -> Vec<Vec<usize>> {
+        let mut by_origin: HashMap<usize, Vec<usize>> = HashMap::new();
+
+        for half_edge in self.boundary_half_edges() {
+            by_origin
+                .entry(self.origin(half_edge))
+                .or_default()
+                .push(half_edge);
+        }
+
+        let mut used = vec![false; self.origins.len()];
+        let mut loops = Vec::new();
+
+        for start in self.boundary_half_edges() {
+            if used[start] {
+                continue;
+            }
+
+            let mut vertices = Vec::new();
+            let mut half_edge = start;
+
+            loop {
+                used[half_edge] = true;
+                vertices.push(self.origin(half_edge));
+
+                let next = by_origin
+                    .get(&self.target(half_edge))
+                    .and_then(|candidates| candidates.iter().find(|&&h| !used[h]));
+
+                match next {
+                    Some(&next) => half_edge = next,
+                    None => break,
+                }
+            }
+
+            loops.push(vertices);
+        }
+
+        loops
+    }
+
+    /// Convert back into a triangle soup, with welded vertices
+    pub fn to_stl_file(&self) -> StlFile {
+        let mut file = StlFile::new();
+
+        for (facet, (&normal, &attribute)) in self.normals.iter().zip(&self.attributes).enumerate()
+        {
+            let [v0, v1, v2] = self.facet_vertices(facet);
+
+            file.push_triangle_with_attribute(
+                Triangle {
+                    normal,
+                    v0: self.points[v0],
+                    v1: self.points[v1],
+                    v2: self.points[v2],
+                },
+                attribute,
+            );
+        }
+
+        file
+    }
+}
+
+impl From<&StlFile> for HalfEdgeMesh {
+    fn from(stl: &StlFile) -> Self {
+        Self::new(stl)
+    }
+}
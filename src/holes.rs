@@ -0,0 +1,113 @@
+use crate::{math::Vec3, HalfEdgeMesh, Normal, Point, Triangle};
+
+/// Facets closing each hole of `mesh` with at most `max_edges` edges, along
+/// with the number of holes they close
+///
+/// Holes whose boundary does not return to its start, or which pass through
+/// the same vertex twice, are left open.
+pub(crate) fn fill(mesh: &HalfEdgeMesh, max_edges: usize) -> (Vec<Triangle>, usize) {
+    let mut triangles = Vec::new();
+    let mut num_of_filled = 0;
+
+    for vertices in mesh.boundary_loops() {
+        if vertices.len() < 3 || vertices.len() > max_edges || !is_closed(mesh, &vertices) {
+            continue;
+        }
+
+        if (1..vertices.len()).any(|idx| vertices[..idx].contains(&vertices[idx])) {
+            continue;
+        }
+
+        let points = vertices
+            .iter()
+            .map(|&vertex| mesh.point(vertex))
+            .collect::<Vec<_>>();
+
+        // the loop follows the winding of the facets around the hole, so the
+        // facets filling it are wound the other way
+        triangles.extend(triangulate(&points).into_iter().map(|[a, b, c]| {
+            Triangle {
+                normal: Normal {
+                    i: 0.0,
+                    j: 0.0,
+                    k: 0.0,
+                },
+                v0: points[a],
+                v1: points[c],
+                v2: points[b],
+            }
+            .with_computed_normal()
+        }));
+
+        num_of_filled += 1;
+    }
+
+    (triangles, num_of_filled)
+}
+
+/// Whether a boundary edge leads from the last vertex of a loop to the first
+fn is_closed(mesh: &HalfEdgeMesh, vertices: &[usize]) -> bool {
+    let (first, last) = (vertices[0], vertices[vertices.len() - 1]);
+
+    mesh.outgoing(last)
+        .iter()
+        .any(|&half_edge| mesh.twin(half_edge).is_none() && mesh.target(half_edge) == first)
+}
+
+/// Split a polygon into triangles of minimum total area, each given as
+/// indices into `points` in the same winding as the polygon
+///
+/// Unlike ear clipping, this does not require the polygon to be planar.
+/// It takes O(n³) time and O(n²) memory in the number of vertices.
+fn triangulate(points: &[Point]) -> Vec<[usize; 3]> {
+    let n = points.len();
+    let points = points.iter().map(|&p| Vec3::from(p)).collect::<Vec<_>>();
+    let area = |a: usize, b: usize, c: usize| {
+        (points[b] - points[a])
+            .cross(points[c] - points[a])
+            .length()
+            / 2.0
+    };
+
+    // `weights[i][j]` is the least area triangulating the vertices from `i`
+    // to `j`, and `splits[i][j]` the third vertex of the facet on edge `i, j`
+    let mut weights = vec![vec![0.0; n]; n];
+    let mut splits = vec![vec![0; n]; n];
+
+    for len in 2..n {
+        for i in 0..n - len {
+            let j = i + len;
+
+            // `len` is at least 2, so there is at least one candidate
+            let (weight, k) = ((i + 1)..j)
+                .map(|k| (weights[i][k] + weights[k][j] + area(i, k, j), k))
+                .reduce(|best, candidate| {
+                    if candidate.0 < best.0 {
+                        candidate
+                    } else {
+                        best
+                    }
+                })
+                .unwrap();
+            weights[i][j] = weight;
+            splits[i][j] = k;
+        }
+    }
+
+    let mut triangles = Vec::with_capacity(n - 2);
+    let mut stack = vec![(0, n - 1)];
+
+    while let Some((i, j)) = stack.pop() {
+        if j - i < 2 {
+            continue;
+        }
+
+        let k = splits[i][j];
+        triangles.push([i, k, j]);
+
+        stack.push((i, k));
+        stack.push((k, j));
+    }
+
+    triangles
+}
@@ -3,30 +3,74 @@
 use std::{
     collections::{hash_map::Entry, HashMap},
     fs,
-    io::{self, Result as IoResult, Write},
+    hash::Hash,
+    io::{Read, Write},
+    iter::FromIterator,
     path::Path,
 };
 
+mod adjacency;
 mod ascii;
 mod bbox;
 mod binary;
+mod cleanup;
+mod color;
+#[cfg(feature = "compression")]
+mod compression;
+mod double;
 mod error;
+mod format;
+mod half_edge;
+mod holes;
+mod mass;
+mod math;
+mod options;
+mod orient;
+mod reader;
+mod topology;
 mod union_find;
+mod validate;
 mod vertex;
+mod view;
+mod weld;
+mod writer;
 
+use adjacency::Adjacency;
 use ascii::AsciiParser;
 pub use bbox::BoundingBox;
 use binary::BinaryParser;
-pub use error::{StlError, StlResult};
+pub use cleanup::CleanupSummary;
+pub use color::{Color, ColorFormat, Material};
+#[cfg(feature = "compression")]
+use compression::Compression;
+pub use double::{Normal64, Point64, StlFile64, Triangle64};
+pub use error::{
+    LimitExceeded, Location, ParseError, ParseErrorKind, ParseWarning, StlError, StlResult,
+};
+pub use format::StlFormat;
+pub use half_edge::HalfEdgeMesh;
+pub use mass::MassProperties;
+pub use options::ParseOptions;
+pub use reader::StlReader;
+pub use topology::TopologyReport;
 use union_find::UnionFind;
-pub use vertex::{Normal, Point, Triangle, VertexWithNormal};
+pub use validate::NormalDeviation;
+pub use vertex::{Normal, Point, Triangle, TriangleMut, VertexWithNormal};
 use vertex::{TriangleIterator, VertexWithNormalIterator};
+pub use view::BinaryStlView;
+#[cfg(feature = "mmap")]
+pub use view::MmapStl;
+use weld::Welded;
+pub use writer::{AsciiOptions, FloatFormat, StlWriter};
 
 /// A parsed STL file
 #[derive(Debug, Clone, PartialEq)]
 pub struct StlFile {
     normals: Vec<Normal>,
     vertices: Vec<f32>,
+    attributes: Vec<u16>,
+    header: [u8; 80],
+    name: String,
 }
 
 impl StlFile {
@@ -36,21 +80,233 @@ impl StlFile {
     /// # use stl::StlFile;
     /// let stl = StlFile::from_path("./foo.stl").unwrap();
     /// ```
+    ///
+    /// With the `compression` feature, gzip and zip files are decompressed
+    /// first. The STL files in a zip archive are merged into a single file;
+    /// use [`StlFile::entries_from_path`] to keep them separate.
     pub fn from_path(path: impl AsRef<Path>) -> StlResult<Self> {
-        let buffer = fs::read(path)?;
+        Self::from_path_with_options(path, &ParseOptions::default()).map(|(file, _)| file)
+    }
+
+    /// Read `reader` to the end and parse it in the same way as
+    /// [`StlFile::from_path`]
+    pub fn from_reader(reader: impl Read) -> StlResult<Self> {
+        Self::from_reader_with_options(reader, &ParseOptions::default()).map(|(file, _)| file)
+    }
+
+    /// Parse an STL from a file path in the same way as
+    /// [`StlFile::parse_with_options`], decompressing it as in
+    /// [`StlFile::from_path`]
+    ///
+    /// `max_input_len` limits both the size of the file and the total
+    /// decompressed size of the STLs within it, so a small archive cannot
+    /// expand to fill memory. The facet and allocation limits apply to all the
+    /// entries of a zip archive together, and are checked as each is parsed.
+    /// The warnings of each entry are concatenated.
+    pub fn from_path_with_options(
+        path: impl AsRef<Path>,
+        options: &ParseOptions,
+    ) -> StlResult<(Self, Vec<ParseWarning>)> {
+        Self::from_reader_with_options(fs::File::open(path)?, options)
+    }
+
+    /// Read `reader` to the end and parse it in the same way as
+    /// [`StlFile::from_path_with_options`]
+    pub fn from_reader_with_options(
+        reader: impl Read,
+        options: &ParseOptions,
+    ) -> StlResult<(Self, Vec<ParseWarning>)> {
+        let buffer = options.read_to_end(reader)?;
+
+        Self::parse_decompressed(&buffer, options)
+    }
+
+    /// Parse each STL in a zip archive, along with the name of its entry
+    ///
+    /// Entries are returned in the order they are stored, and entries whose
+    /// names do not end in `.stl` are skipped. Any other input is parsed as a
+    /// single entry named after the file, without a trailing `.gz`.
+    ///
+    /// ```no_run
+    /// # use stl::StlFile;
+    /// for (name, stl) in StlFile::entries_from_path("./parts.zip").unwrap() {
+    ///     println!("{}: {} facets", name, stl.facet_count());
+    /// }
+    /// ```
+    #[cfg(feature = "compression")]
+    pub fn entries_from_path(path: impl AsRef<Path>) -> StlResult<Vec<(String, Self)>> {
+        Self::entries_from_path_with_options(path, &ParseOptions::default())
+            .map(Self::without_warnings)
+    }
+
+    /// Read `reader` to the end and parse it in the same way as
+    /// [`StlFile::entries_from_path`]
+    ///
+    /// Input other than a zip archive is returned as a single entry with an
+    /// empty name.
+    #[cfg(feature = "compression")]
+    pub fn entries_from_reader(reader: impl Read) -> StlResult<Vec<(String, Self)>> {
+        Self::entries_from_reader_with_options(reader, &ParseOptions::default())
+            .map(Self::without_warnings)
+    }
+
+    /// Parse each STL in a zip archive as in [`StlFile::entries_from_path`],
+    /// with the options and limits of [`StlFile::from_path_with_options`]
+    #[cfg(feature = "compression")]
+    pub fn entries_from_path_with_options(
+        path: impl AsRef<Path>,
+        options: &ParseOptions,
+    ) -> StlResult<Vec<(String, Self, Vec<ParseWarning>)>> {
+        let path = path.as_ref();
+        let buffer = options.read_to_end(fs::File::open(path)?)?;
+
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .unwrap_or_default();
+
+        Self::parse_entries(&buffer, name.trim_end_matches(".gz"), options)
+    }
+
+    /// Read `reader` to the end and parse it in the same way as
+    /// [`StlFile::entries_from_path_with_options`]
+    ///
+    /// Input other than a zip archive is returned as a single entry with an
+    /// empty name.
+    #[cfg(feature = "compression")]
+    pub fn entries_from_reader_with_options(
+        reader: impl Read,
+        options: &ParseOptions,
+    ) -> StlResult<Vec<(String, Self, Vec<ParseWarning>)>> {
+        let buffer = options.read_to_end(reader)?;
+
+        Self::parse_entries(&buffer, "", options)
+    }
+
+    #[cfg(feature = "compression")]
+    fn without_warnings(entries: Vec<(String, Self, Vec<ParseWarning>)>) -> Vec<(String, Self)> {
+        entries
+            .into_iter()
+            .map(|(name, file, _)| (name, file))
+            .collect()
+    }
+
+    #[cfg(feature = "compression")]
+    fn parse_entries(
+        buffer: &[u8],
+        name: &str,
+        options: &ParseOptions,
+    ) -> StlResult<Vec<(String, Self, Vec<ParseWarning>)>> {
+        let parse = |name: String, buffer: &[u8], options: &ParseOptions| {
+            let (file, warnings) = Self::parse_with_options(buffer, options)?;
+            Ok((name, file, warnings))
+        };
+
+        match Compression::detect(buffer) {
+            Compression::Zip => {
+                let mut num_of_facets = 0;
+
+                // every entry counts towards the same limits
+                compression::unzip(buffer, options, |name, contents| {
+                    let entry = parse(name, &contents, &options.after_facets(num_of_facets))
+                        .map_err(|err| options.restore_limit(err))?;
+
+                    num_of_facets += entry.1.facet_count() as usize;
+                    Ok(entry)
+                })
+            }
+            Compression::Gzip => Ok(vec![parse(
+                name.to_owned(),
+                &compression::gunzip(buffer, options)?,
+                options,
+            )?]),
+            Compression::None => Ok(vec![parse(name.to_owned(), buffer, options)?]),
+        }
+    }
+
+    /// Parse a buffer which, with the `compression` feature, may be gzip or
+    /// zip compressed
+    fn parse_decompressed(
+        buffer: &[u8],
+        options: &ParseOptions,
+    ) -> StlResult<(Self, Vec<ParseWarning>)> {
+        #[cfg(feature = "compression")]
+        match Compression::detect(buffer) {
+            Compression::Gzip => {
+                return Self::parse_with_options(&compression::gunzip(buffer, options)?, options)
+            }
+            Compression::Zip => {
+                let mut entries = Self::parse_entries(buffer, "", options)?.into_iter();
+
+                // `unzip` fails if there are no entries
+                let (mut file, mut warnings) = entries
+                    .next()
+                    .map_or_else(Default::default, |(_, file, warnings)| (file, warnings));
+
+                for (_, entry, entry_warnings) in entries {
+                    file.append(entry);
+                    warnings.extend(entry_warnings);
+                }
+
+                return Ok((file, warnings));
+            }
+            Compression::None => {}
+        }
 
-        Self::parse(&buffer)
+        Self::parse_with_options(buffer, options)
     }
 
-    pub(crate) fn new() -> Self {
+    /// An empty file with a header of all zeros and no name
+    ///
+    /// ```
+    /// # use stl::{Normal, Point, StlFile, Triangle};
+    /// let mut stl = StlFile::new();
+    ///
+    /// stl.push_triangle(Triangle {
+    ///     normal: Normal { i: 0.0, j: 0.0, k: 1.0 },
+    ///     v0: Point { x: 0.0, y: 0.0, z: 0.0 },
+    ///     v1: Point { x: 1.0, y: 0.0, z: 0.0 },
+    ///     v2: Point { x: 0.0, y: 1.0, z: 0.0 },
+    /// });
+    ///
+    /// stl.triangle_mut(0).unwrap().v2.z = 1.0;
+    ///
+    /// assert_eq!(stl.facet_count(), 1);
+    /// assert_eq!(stl.vertex_buffer()[8], 1.0);
+    /// ```
+    pub fn new() -> Self {
         Self {
             normals: Vec::new(),
             vertices: Vec::new(),
+            attributes: Vec::new(),
+            header: [0; 80],
+            name: String::new(),
         }
     }
 
-    pub(crate) fn add_triangle(&mut self, v: Triangle) {
+    /// A file containing `triangles`, each with an attribute of zero
+    pub fn from_triangles(triangles: impl IntoIterator<Item = Triangle>) -> Self {
+        let mut file = Self::new();
+        file.extend(triangles);
+        file
+    }
+
+    /// Move the facets of `other` to the end of this file
+    pub(crate) fn append(&mut self, other: Self) {
+        self.normals.extend(other.normals);
+        self.vertices.extend(other.vertices);
+        self.attributes.extend(other.attributes);
+    }
+
+    /// Append a facet with an attribute of zero
+    pub fn push_triangle(&mut self, triangle: Triangle) {
+        self.push_triangle_with_attribute(triangle, 0);
+    }
+
+    /// Append a facet along with its 2-byte attribute word
+    pub fn push_triangle_with_attribute(&mut self, v: Triangle, attribute: u16) {
         self.normals.push(v.normal);
+        self.attributes.push(attribute);
 
         self.vertices.push(v.v0.x);
         self.vertices.push(v.v0.y);
@@ -71,37 +327,288 @@ impl StlFile {
     /// # use stl::StlFile;
     /// let stl = StlFile::parse(b"solid foo\nendsolid\n").unwrap();
     /// ```
+    ///
+    /// The format is guessed using [`StlFormat::detect`]. If the buffer fails to
+    /// parse in the detected format, the other format is attempted before
+    /// reporting the error from the first attempt.
     pub fn parse(buffer: &[u8]) -> StlResult<Self> {
-        let mut start = 0;
-        while let Some(&b) = buffer.get(start) {
-            if b.is_ascii_whitespace() {
-                start += 1;
-            } else {
-                break;
-            }
+        let format = StlFormat::detect(buffer);
+
+        Self::parse_format(buffer, format)
+            .or_else(|err| Self::parse_format(buffer, format.other()).map_err(|_| err))
+    }
+
+    fn parse_format(buffer: &[u8], format: StlFormat) -> StlResult<Self> {
+        match format {
+            StlFormat::Ascii => Self::parse_ascii(buffer),
+            StlFormat::Binary => Self::parse_binary(buffer),
         }
+    }
 
-        let buffer = &buffer[start..];
+    /// Parse an STL file, returning warnings about any input that was tolerated
+    ///
+    /// The format is detected in the same way as [`StlFile::parse`], except
+    /// that lenient parsing matches the ASCII keywords in any case.
+    pub fn parse_with_options(
+        buffer: &[u8],
+        options: &ParseOptions,
+    ) -> StlResult<(Self, Vec<ParseWarning>)> {
+        let parse = |format, options| -> StlResult<(Self, Vec<ParseWarning>)> {
+            match format {
+                StlFormat::Ascii => Ok(AsciiParser::new(buffer, options)?.parse()?),
+                StlFormat::Binary => Ok(BinaryParser::new(buffer, options)?.parse()?),
+            }
+        };
 
-        if buffer.starts_with(b"solid") {
-            Ok(AsciiParser::new(buffer)?.parse())
+        let format = if options.strict {
+            StlFormat::detect(buffer)
         } else {
-            Ok(BinaryParser::new(buffer)?.parse())
-        }
+            StlFormat::detect_ignoring_case(buffer)
+        };
+
+        let result = match parse(format, *options) {
+            // in lenient mode almost any input parses as a truncated binary
+            // file, so prefer ASCII if it also succeeds, and its error if the
+            // input claims to be ASCII
+            Ok((file, warnings))
+                if warnings
+                    .iter()
+                    .any(|w| matches!(w, ParseWarning::FacetCountMismatch { .. })) =>
+            {
+                match parse(StlFormat::Ascii, *options) {
+                    Err(err) if format::starts_with_solid(buffer, !options.strict) => Err(err),
+                    result => Ok(result.unwrap_or((file, warnings))),
+                }
+            }
+            Ok(result) => Ok(result),
+            // for the same reason, malformed ASCII only falls back to a binary
+            // file whose facet count matches its length
+            Err(err) => {
+                let options = match format {
+                    StlFormat::Ascii => ParseOptions {
+                        strict: true,
+                        ..*options
+                    },
+                    StlFormat::Binary => *options,
+                };
+
+                parse(format.other(), options).map_err(|_| err)
+            }
+        };
+
+        result.map(|(mut file, warnings)| {
+            if options.recompute_normals {
+                file.recompute_normals();
+            }
+
+            (file, warnings)
+        })
+    }
+
+    /// Parse an STL file, reporting both input that was tolerated and facets
+    /// which look suspicious
+    ///
+    /// This combines the warnings from [`StlFile::parse_with_options`] with
+    /// those from [`StlFile::validate`].
+    ///
+    /// ```
+    /// # use stl::{ParseOptions, ParseWarning, StlFile};
+    /// let (stl, warnings) = StlFile::parse_with_report(b"solid foo
+    /// facet normal 0 0 -1 outer loop vertex 0 0 0 vertex 1 0 0 vertex 0 1 0 endloop endfacet
+    /// endsolid foo", &ParseOptions::default()).unwrap();
+    ///
+    /// assert_eq!(stl.facet_count(), 1);
+    /// assert_eq!(warnings, vec![ParseWarning::NormalMismatch { facet: 0, angle: 180.0 }]);
+    /// ```
+    pub fn parse_with_report(
+        buffer: &[u8],
+        options: &ParseOptions,
+    ) -> StlResult<(Self, Vec<ParseWarning>)> {
+        let (file, mut warnings) = Self::parse_with_options(buffer, options)?;
+
+        warnings.extend(file.validate());
+
+        Ok((file, warnings))
     }
 
     /// Parse a buffer known to be in ASCII format
+    ///
+    /// If the buffer contains multiple solids, their facets are merged into a
+    /// single file which takes the name of the first. Use
+    /// [`StlFile::parse_ascii_solids`] to keep them separate.
     pub fn parse_ascii(buffer: &[u8]) -> StlResult<Self> {
-        Ok(AsciiParser::new(buffer)?.parse())
+        Ok(AsciiParser::new(buffer, ParseOptions::default())?
+            .parse()?
+            .0)
+    }
+
+    /// Parse an STL file using every thread in the rayon thread pool
+    ///
+    /// Binary files are split into fixed-size chunks of facets, and ASCII files
+    /// are split at `facet` boundaries. The result is identical to
+    /// [`StlFile::parse`], and likewise no [`ParseOptions`] limits apply.
+    #[cfg(feature = "rayon")]
+    pub fn par_parse(buffer: &[u8]) -> StlResult<Self> {
+        let format = StlFormat::detect(buffer);
+
+        let parse = |format| match format {
+            StlFormat::Ascii => Self::par_parse_ascii(buffer),
+            StlFormat::Binary => Self::par_parse_binary(buffer),
+        };
+
+        parse(format).or_else(|err| parse(format.other()).map_err(|_| err))
+    }
+
+    /// Parse a buffer known to be in ASCII format in parallel
+    #[cfg(feature = "rayon")]
+    pub fn par_parse_ascii(buffer: &[u8]) -> StlResult<Self> {
+        Ok(AsciiParser::new(buffer, ParseOptions::default())?
+            .parallel()
+            .parse()?
+            .0)
+    }
+
+    /// Parse a buffer known to be in binary format in parallel
+    #[cfg(feature = "rayon")]
+    pub fn par_parse_binary(buffer: &[u8]) -> StlResult<Self> {
+        // `BinaryParser::new` would reserve space for every facet, which
+        // `binary::par_parse` then allocates again
+        let num_of_facets = binary::read_facet_count(buffer)?;
+
+        Ok(binary::par_parse(buffer, num_of_facets))
+    }
+
+    /// Parse each named solid in an ASCII buffer into its own file
+    ///
+    /// ```
+    /// # use stl::StlFile;
+    /// let solids = StlFile::parse_ascii_solids(b"solid foo
+    /// endsolid foo
+    /// solid bar
+    /// endsolid bar
+    /// ").unwrap();
+    ///
+    /// assert_eq!(solids[0].name(), "foo");
+    /// assert_eq!(solids[1].name(), "bar");
+    /// ```
+    pub fn parse_ascii_solids(buffer: &[u8]) -> StlResult<Vec<Self>> {
+        Ok(AsciiParser::new(buffer, ParseOptions::default())?
+            .parse_solids()?
+            .0)
     }
 
     /// Parse a buffer known to be in binary format
     pub fn parse_binary(buffer: &[u8]) -> StlResult<Self> {
-        Ok(BinaryParser::new(buffer)?.parse())
+        Ok(BinaryParser::new(buffer, ParseOptions::default())?
+            .parse()?
+            .0)
+    }
+
+    /// The 80-byte header of a binary STL
+    ///
+    /// Exporters frequently store metadata here. Files parsed from ASCII, or
+    /// constructed in memory, have a header of all zeros.
+    pub fn header(&self) -> &[u8; 80] {
+        &self.header
+    }
+
+    /// Replace the header written by [`StlFile::write_binary`]
+    pub fn set_header(&mut self, header: [u8; 80]) {
+        self.header = header;
+    }
+
+    /// The name following `solid` in an ASCII STL
+    ///
+    /// Files parsed from binary, or constructed in memory, have an empty name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn set_name(&mut self, name: impl Into<String>) {
+        self.name = name.into();
+    }
+
+    /// The 2-byte attribute word stored after each facet in a binary STL
+    ///
+    /// The STL format does not specify its meaning, but it is commonly used to
+    /// store a color. See [`StlFile::facet_color`]. Files parsed from ASCII
+    /// have attributes of zero.
+    pub fn attributes(&self) -> &[u16] {
+        &self.attributes
+    }
+
+    pub fn attributes_mut(&mut self) -> &mut [u16] {
+        &mut self.attributes
+    }
+
+    /// Decode the color of a facet, if it has one
+    ///
+    /// Returns `None` if `facet` is out of bounds.
+    pub fn facet_color(&self, facet: usize, format: ColorFormat) -> Option<Color> {
+        format.decode(*self.attributes.get(facet)?)
+    }
+
+    /// Encode the color of a facet into its attribute bytes
+    ///
+    /// A color of `None` marks the facet as using the default color.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `facet` is out of bounds
+    pub fn set_facet_color(&mut self, facet: usize, format: ColorFormat, color: Option<Color>) {
+        self.attributes[facet] = format.encode(color);
+    }
+
+    /// The default color for the whole part, stored as RGBA after `COLOR=` in
+    /// the header of files exported by Materialise Magics
+    pub fn header_color(&self) -> Option<[u8; 4]> {
+        let value = color::find_header_value(&self.header, color::COLOR_KEY, 4)?;
+
+        Some([value[0], value[1], value[2], value[3]])
+    }
+
+    /// Store a default color in the header
+    ///
+    /// An existing `COLOR=` value is replaced in place. Otherwise the key is
+    /// written near the end of the header, leaving room for `MATERIAL=`.
+    pub fn set_header_color(&mut self, rgba: [u8; 4]) {
+        color::set_header_value(&mut self.header, color::COLOR_KEY, &rgba, color::COLOR_SLOT);
+    }
+
+    /// Material properties stored after `MATERIAL=` in the header of files
+    /// exported by Materialise Magics
+    pub fn header_material(&self) -> Option<Material> {
+        let v = color::find_header_value(&self.header, color::MATERIAL_KEY, 12)?;
+
+        Some(Material {
+            diffuse: [v[0], v[1], v[2], v[3]],
+            specular: [v[4], v[5], v[6], v[7]],
+            ambient: [v[8], v[9], v[10], v[11]],
+        })
+    }
+
+    /// Store material properties in the header
+    ///
+    /// An existing `MATERIAL=` value is replaced in place. Otherwise the key
+    /// is written at the end of the header.
+    pub fn set_header_material(&mut self, material: Material) {
+        let mut value = [0; 12];
+        value[..4].copy_from_slice(&material.diffuse);
+        value[4..8].copy_from_slice(&material.specular);
+        value[8..].copy_from_slice(&material.ambient);
+
+        color::set_header_value(
+            &mut self.header,
+            color::MATERIAL_KEY,
+            &value,
+            color::MATERIAL_SLOT,
+        );
     }
 
     /// Write this file in binary STL format
     ///
+    /// The header is taken from [`StlFile::header`].
+    ///
     /// ```no_run
     /// # use stl::StlFile;
     /// let stl = StlFile::from_path("./foo.stl").unwrap();
@@ -109,34 +616,193 @@ impl StlFile {
     /// stl.write_binary(&mut file).unwrap();
     /// ```
     pub fn write_binary(&self, buffer: &mut dyn Write) -> StlResult<()> {
-        buffer.write_all(&[0; 80])?;
-        buffer.write_all(&self.facet_count().to_le_bytes())?;
-
-        fn write_vec3(x: f32, y: f32, z: f32, buffer: &mut dyn Write) -> StlResult<()> {
-            buffer.write_all(&x.to_le_bytes())?;
-            buffer.write_all(&y.to_le_bytes())?;
-            buffer.write_all(&z.to_le_bytes())?;
+        let mut writer = StlWriter::binary_with_header(buffer, self.header, self.facet_count())?;
 
-            Ok(())
+        for (triangle, &attribute) in self.triangles().zip(&self.attributes) {
+            writer.write_triangle_with_attribute(triangle, attribute)?;
         }
 
+        writer.finish()?;
+
+        Ok(())
+    }
+
+    /// Write this file in ASCII STL format as a single solid called `name`
+    ///
+    /// Floats are written in their shortest round-trip representation. See
+    /// [`StlFile::write_ascii_with_options`] to configure formatting.
+    pub fn write_ascii(&self, buffer: &mut dyn Write, name: &str) -> StlResult<()> {
+        self.write_ascii_with_options(buffer, name, &AsciiOptions::default())
+    }
+
+    /// Write this file in ASCII STL format using the given formatting options
+    pub fn write_ascii_with_options(
+        &self,
+        buffer: &mut dyn Write,
+        name: &str,
+        options: &AsciiOptions,
+    ) -> StlResult<()> {
+        let mut writer = StlWriter::ascii_with_options(buffer, name, options.clone())?;
+
         for triangle in self.triangles() {
-            write_vec3(
-                triangle.normal.i,
-                triangle.normal.j,
-                triangle.normal.k,
-                buffer,
-            )?;
-            write_vec3(triangle.v0.x, triangle.v0.y, triangle.v0.z, buffer)?;
-            write_vec3(triangle.v1.x, triangle.v1.y, triangle.v1.z, buffer)?;
-            write_vec3(triangle.v2.x, triangle.v2.y, triangle.v2.z, buffer)?;
-
-            buffer.write_all(&[0; 2])?;
+            writer.write_triangle(triangle)?;
         }
 
+        writer.finish()?;
+
         Ok(())
     }
 
+    /// Write multiple solids to a single ASCII STL, each under its own name
+    ///
+    /// Names are written as in [`StlWriter::ascii`], so solids without one,
+    /// such as those read from binary files, can still be parsed back.
+    pub fn write_ascii_solids(
+        solids: &[Self],
+        buffer: &mut dyn Write,
+        options: &AsciiOptions,
+    ) -> StlResult<()> {
+        for solid in solids {
+            solid.write_ascii_with_options(buffer, &solid.name, options)?;
+        }
+
+        Ok(())
+    }
+
+    /// Convert to double precision without loss, for transforms which would
+    /// otherwise lose precision
+    pub fn to_f64(&self) -> StlFile64 {
+        StlFile64::from(self)
+    }
+
+    /// The facet at index `facet`, or `None` if it is out of bounds
+    pub fn triangle(&self, facet: usize) -> Option<Triangle> {
+        let normal = *self.normals.get(facet)?;
+        let v = &self.vertices[(facet * 9)..(facet * 9 + 9)];
+
+        Some(Triangle {
+            normal,
+            v0: Point {
+                x: v[0],
+                y: v[1],
+                z: v[2],
+            },
+            v1: Point {
+                x: v[3],
+                y: v[4],
+                z: v[5],
+            },
+            v2: Point {
+                x: v[6],
+                y: v[7],
+                z: v[8],
+            },
+        })
+    }
+
+    /// Edit the facet at index `facet` in place, or `None` if it is out of bounds
+    ///
+    /// Changes are written back when the returned guard is dropped.
+    pub fn triangle_mut(&mut self, facet: usize) -> Option<TriangleMut<'_>> {
+        let triangle = self.triangle(facet)?;
+
+        Some(TriangleMut::new(
+            triangle,
+            &mut self.normals[facet],
+            &mut self.vertices[(facet * 9)..(facet * 9 + 9)],
+        ))
+    }
+
+    /// Remove the facet at index `facet`, shifting all facets after it down
+    ///
+    /// # Panics
+    ///
+    /// Panics if `facet` is out of bounds
+    pub fn remove_triangle(&mut self, facet: usize) -> Triangle {
+        let triangle = match self.triangle(facet) {
+            Some(triangle) => triangle,
+            None => panic!(
+                "facet index {} is out of bounds for {} facets",
+                facet,
+                self.facet_count()
+            ),
+        };
+
+        self.normals.remove(facet);
+        self.attributes.remove(facet);
+        self.vertices.drain((facet * 9)..(facet * 9 + 9));
+
+        triangle
+    }
+
+    /// Keep only the facets for which `f` returns `true`, preserving their order
+    pub fn retain_triangles(&mut self, mut f: impl FnMut(&Triangle) -> bool) {
+        let mut kept = 0;
+
+        for facet in 0..self.normals.len() {
+            // `facet` is always in bounds
+            let triangle = self.triangle(facet).unwrap();
+
+            if !f(&triangle) {
+                continue;
+            }
+
+            self.normals[kept] = self.normals[facet];
+            self.attributes[kept] = self.attributes[facet];
+            self.vertices
+                .copy_within((facet * 9)..(facet * 9 + 9), kept * 9);
+
+            kept += 1;
+        }
+
+        self.normals.truncate(kept);
+        self.attributes.truncate(kept);
+        self.vertices.truncate(kept * 9);
+    }
+
+    /// Remove facets with two or more vertices which compare equal, and those
+    /// with an area of at most `area_eps`
+    ///
+    /// Facets with NaN or infinite coordinates are kept.
+    ///
+    /// ```
+    /// # use stl::StlFile;
+    /// let mut stl = StlFile::parse(b"solid foo
+    /// facet normal 0 0 1 outer loop vertex 0 0 0 vertex 1 0 0 vertex 0 1 0 endloop endfacet
+    /// facet normal 0 0 0 outer loop vertex 0 0 0 vertex 1 0 0 vertex 2 0 0 endloop endfacet
+    /// endsolid foo").unwrap();
+    ///
+    /// let summary = stl.remove_degenerate(1e-9);
+    /// assert_eq!(summary.zero_area, 1);
+    /// assert_eq!(stl.facet_count(), 1);
+    /// ```
+    pub fn remove_degenerate(&mut self, area_eps: f32) -> CleanupSummary {
+        let (keep, summary) = cleanup::degenerate(self.triangles(), area_eps);
+
+        self.retain_flagged(keep);
+        summary
+    }
+
+    /// Remove facets with the same vertices as an earlier facet, whether they
+    /// are wound the same way or back to back
+    ///
+    /// Vertices are matched in the same way as [`StlFile::split_islands`],
+    /// and the first of each set of duplicates is kept.
+    pub fn remove_duplicate_facets(&mut self) -> CleanupSummary {
+        let (keep, summary) = cleanup::duplicates(self.triangles());
+
+        self.retain_flagged(keep);
+        summary
+    }
+
+    /// Keep only the facets whose flag is `true`
+    fn retain_flagged(&mut self, keep: Vec<bool>) {
+        let mut keep = keep.into_iter();
+
+        // `keep` has one flag per facet
+        self.retain_triangles(|_| keep.next().unwrap());
+    }
+
     /// Contiguous slice of memory containing all vertices
     pub fn vertex_buffer(&self) -> &[f32] {
         &self.vertices
@@ -158,10 +824,109 @@ impl StlFile {
         self.normals.len() as u32
     }
 
-    pub fn normals<'a>(&'a self) -> &'a [Normal] {
+    pub fn normals(&self) -> &[Normal] {
         &self.normals
     }
 
+    /// Replace every stored normal with the unit normal implied by the
+    /// counter-clockwise winding of its vertices
+    ///
+    /// Facets whose vertices are collinear or coincident are given a normal of
+    /// zero.
+    pub fn recompute_normals(&mut self) {
+        for facet in 0..self.normals.len() {
+            // `facet` is always in bounds
+            self.normals[facet] = self.triangle(facet).unwrap().with_computed_normal().normal;
+        }
+    }
+
+    /// Reverse the winding of facets so that neighboring facets wind
+    /// consistently, and each closed shell has its normals pointing outward
+    ///
+    /// Winding is propagated across edges shared by exactly two facets, with
+    /// vertices matched in the same way as [`StlFile::split_islands`]. Each
+    /// closed set of connected facets, where every edge is shared by exactly
+    /// two facets, is then flipped as a whole if it encloses a negative
+    /// volume. Open surfaces enclose no volume, so are only made consistent.
+    ///
+    /// Flipped facets have two vertices swapped and their normal negated.
+    /// Returns the number of facets flipped.
+    pub fn orient_consistently(&mut self) -> usize {
+        let flips = orient::orientation_flips(&Adjacency::new(self.triangles()));
+
+        let mut num_of_flipped = 0;
+
+        for (facet, _) in flips.into_iter().enumerate().filter(|&(_, flip)| flip) {
+            // `facet` is always in bounds
+            self.triangle_mut(facet).unwrap().reverse_winding();
+
+            num_of_flipped += 1;
+        }
+
+        num_of_flipped
+    }
+
+    /// Facets whose stored normal points more than `threshold` degrees away
+    /// from the normal implied by the winding of their vertices
+    ///
+    /// Facets with a stored normal of zero are always reported. Facets whose
+    /// vertices are collinear or coincident have no meaningful normal and are
+    /// never reported.
+    ///
+    /// ```
+    /// # use stl::StlFile;
+    /// let stl = StlFile::parse(b"solid foo
+    /// facet normal 0 1 0 outer loop vertex 0 0 0 vertex 1 0 0 vertex 0 1 0 endloop endfacet
+    /// endsolid foo").unwrap();
+    ///
+    /// let report = stl.normal_deviation_report(10.0);
+    /// assert_eq!(report[0].facet, 0);
+    /// assert_eq!(report[0].angle, Some(90.0));
+    /// ```
+    pub fn normal_deviation_report(&self, threshold: f32) -> Vec<NormalDeviation> {
+        validate::normal_deviations(self.triangles(), threshold)
+    }
+
+    /// Check each facet for NaN or infinite coordinates, zero area, and a
+    /// stored normal which disagrees with the winding of its vertices
+    ///
+    /// Stored normals of zero are not checked, as exporters commonly omit them.
+    pub fn validate(&self) -> Vec<ParseWarning> {
+        validate::validate(self.triangles())
+    }
+
+    /// Move every vertex onto the first vertex within `epsilon` of it, so
+    /// that nearby vertices become identical
+    ///
+    /// Vertices are matched by their distance rather than the fixed grid used
+    /// by the equality of [`Point`], so this works in any unit. A vertex is
+    /// only moved onto a vertex which was itself kept, so vertices spaced
+    /// just under `epsilon` apart do not all collapse into one. An `epsilon`
+    /// of zero welds only identical vertices.
+    ///
+    /// Returns the number of distinct vertices remaining.
+    ///
+    /// ```
+    /// # use stl::StlFile;
+    /// let mut stl = StlFile::parse(b"solid foo
+    /// facet normal 0 0 1 outer loop vertex 0 0 0 vertex 1 0 0 vertex 0 1 0 endloop endfacet
+    /// facet normal 0 0 1 outer loop vertex 1.001 0 0 vertex 1 1 0 vertex 0 1.001 0 endloop endfacet
+    /// endsolid foo").unwrap();
+    ///
+    /// assert_eq!(stl.weld_vertices(0.01), 4);
+    /// assert_eq!(stl.triangle(1).unwrap().v0, stl.triangle(0).unwrap().v1);
+    /// ```
+    pub fn weld_vertices(&mut self, epsilon: f32) -> usize {
+        let welded = Welded::new(self.vertices(), epsilon);
+
+        for (vertex, &idx) in self.vertices.chunks_exact_mut(3).zip(&welded.indices) {
+            let point = welded.points[idx as usize];
+            vertex.copy_from_slice(&[point.x, point.y, point.z]);
+        }
+
+        welded.points.len()
+    }
+
     pub fn index_buffer_vertex_only(&self) -> IndexBuffer {
         IndexBuffer::from_buffer(self.vertex_and_normal_iterator(), push_vertex_only)
     }
@@ -170,6 +935,41 @@ impl StlFile {
         IndexBuffer::from_buffer(self.vertex_and_normal_iterator(), push_vertex_and_normal)
     }
 
+    /// Like [`StlFile::index_buffer_vertex_only`], but with vertices welded
+    /// as in [`StlFile::weld_vertices`]
+    pub fn index_buffer_vertex_only_with_tolerance(&self, epsilon: f32) -> IndexBuffer {
+        let welded = Welded::new(self.vertices(), epsilon);
+
+        let mut vertices = Vec::with_capacity(welded.points.len() * 3);
+        for point in welded.points {
+            push_point(point, &mut vertices);
+        }
+
+        IndexBuffer {
+            vertices,
+            indices: welded.indices,
+        }
+    }
+
+    /// Like [`StlFile::index_buffer_vertex_and_normal`], but with vertices
+    /// welded as in [`StlFile::weld_vertices`]
+    pub fn index_buffer_vertex_and_normal_with_tolerance(&self, epsilon: f32) -> IndexBuffer {
+        let welded = Welded::new(self.vertices(), epsilon);
+
+        let buffer = welded
+            .indices
+            .iter()
+            .zip(self.vertex_and_normal_iterator())
+            .map(|(&idx, v)| {
+                let vertex = welded.points[idx as usize];
+                (idx, VertexWithNormal { vertex, ..v })
+            });
+
+        IndexBuffer::from_buffer(buffer, |(_, v), vertices| {
+            push_vertex_and_normal(v, vertices)
+        })
+    }
+
     pub fn vertices<'a>(&'a self) -> impl Iterator<Item = Point> + 'a {
         self.vertices.chunks_exact(3).map(|chunk| Point {
             x: chunk[0],
@@ -206,17 +1006,192 @@ impl StlFile {
         VertexWithNormalIterator::new(self.vertices(), self.normals())
     }
 
+    /// Find holes, non-manifold geometry and inconsistently wound facets
+    ///
+    /// Vertices are welded wherever their [`Point`]s compare equal, as in
+    /// [`StlFile::split_islands`].
+    ///
+    /// ```
+    /// # use stl::StlFile;
+    /// let stl = StlFile::parse(b"solid foo
+    /// facet normal 0 0 1 outer loop vertex 0 0 0 vertex 1 0 0 vertex 0 1 0 endloop endfacet
+    /// endsolid foo").unwrap();
+    ///
+    /// let report = stl.topology_report();
+    /// assert_eq!(report.boundary_edges.len(), 3);
+    /// assert!(!report.is_watertight());
+    /// ```
+    pub fn topology_report(&self) -> TopologyReport {
+        TopologyReport::new(&Adjacency::new(self.triangles()))
+    }
+
+    /// The vertices around each hole, in the order given by
+    /// [`HalfEdgeMesh::boundary_loops`]
+    ///
+    /// Each loop follows the winding of the facets around it, and its last
+    /// vertex joins back to its first. Boundaries along non-manifold or
+    /// inconsistently wound edges may not close.
+    pub fn boundary_loops(&self) -> Vec<Vec<Point>> {
+        let mesh = HalfEdgeMesh::new(self);
+
+        mesh.boundary_loops()
+            .into_iter()
+            .map(|vertices| vertices.into_iter().map(|v| mesh.point(v)).collect())
+            .collect()
+    }
+
+    /// Close each hole with at most `max_edges` edges by appending facets
+    /// which triangulate its [`StlFile::boundary_loops`] with the least area
+    ///
+    /// New facets are wound consistently with the facets around the hole and
+    /// given normals matching their winding. Holes whose boundary does not
+    /// close, or which pass through the same vertex twice, are left open.
+    ///
+    /// Finding the least area takes time cubic and memory quadratic in the
+    /// number of edges around each hole, so `max_edges` also bounds the cost
+    /// of filling: a hole of 1000 edges needs 16 MB and around 170 million
+    /// steps.
+    ///
+    /// Returns the number of holes filled.
+    ///
+    /// ```
+    /// # use stl::StlFile;
+    /// // a single facet, whose three edges form one hole
+    /// let mut stl = StlFile::parse(b"solid foo
+    /// facet normal 0 0 1 outer loop vertex 0 0 0 vertex 1 0 0 vertex 0 1 0 endloop endfacet
+    /// endsolid foo").unwrap();
+    ///
+    /// assert_eq!(stl.boundary_loops().len(), 1);
+    /// assert_eq!(stl.fill_holes(3), 1);
+    /// assert!(stl.topology_report().is_watertight());
+    ///
+    /// // the new facet lies back to back with the first
+    /// assert_eq!(stl.triangle(1).unwrap().normal.k, -1.0);
+    /// ```
+    pub fn fill_holes(&mut self, max_edges: usize) -> usize {
+        let (triangles, num_of_filled) = holes::fill(&HalfEdgeMesh::new(self), max_edges);
+
+        self.extend(triangles);
+        num_of_filled
+    }
+
+    /// Surface area, volume, centroid and inertia tensor, assuming uniform
+    /// unit density
+    ///
+    /// See [`MassProperties`] for the behavior on open meshes.
+    ///
+    /// ```
+    /// # use stl::StlFile;
+    /// // a tetrahedron with three edges of length 1 along the axes
+    /// let stl = StlFile::parse(b"solid tetrahedron
+    /// facet normal 0 0 -1 outer loop vertex 0 0 0 vertex 0 1 0 vertex 1 0 0 endloop endfacet
+    /// facet normal 0 -1 0 outer loop vertex 0 0 0 vertex 1 0 0 vertex 0 0 1 endloop endfacet
+    /// facet normal -1 0 0 outer loop vertex 0 0 0 vertex 0 0 1 vertex 0 1 0 endloop endfacet
+    /// facet normal 0.577 0.577 0.577 outer loop vertex 1 0 0 vertex 0 1 0 vertex 0 0 1 endloop endfacet
+    /// endsolid tetrahedron").unwrap();
+    ///
+    /// assert!((stl.volume() - 1.0 / 6.0).abs() < 1e-12);
+    ///
+    /// let centroid = stl.centroid().unwrap();
+    /// assert!((centroid.x - 0.25).abs() < 1e-12);
+    /// ```
+    pub fn mass_properties(&self) -> MassProperties {
+        MassProperties::new(self.triangles())
+    }
+
+    /// [`StlFile::mass_properties`] of each island, in the same order as
+    /// [`StlFile::split_islands`]
+    pub fn island_mass_properties(&self) -> Vec<MassProperties> {
+        self.clone()
+            .split_islands()
+            .iter()
+            .map(Self::mass_properties)
+            .collect()
+    }
+
+    /// Total area of every facet
+    pub fn surface_area(&self) -> f64 {
+        self.mass_properties().surface_area
+    }
+
+    /// The volume enclosed by the mesh, which is negative if it is inside out
+    ///
+    /// This is only meaningful for closed meshes.
+    pub fn signed_volume(&self) -> f64 {
+        self.mass_properties().signed_volume
+    }
+
+    /// The absolute value of [`StlFile::signed_volume`]
+    pub fn volume(&self) -> f64 {
+        self.signed_volume().abs()
+    }
+
+    /// The center of mass of the enclosed volume, or `None` if there is none
+    pub fn centroid(&self) -> Option<Point64> {
+        self.mass_properties().centroid
+    }
+
+    /// The inertia tensor about [`StlFile::centroid`], assuming unit density,
+    /// or `None` if the mesh encloses no volume
+    pub fn inertia_tensor(&self) -> Option<[[f64; 3]; 3]> {
+        self.mass_properties().inertia_tensor
+    }
+
+    /// Split the mesh into sets of facets connected by shared vertices
+    ///
+    /// Islands are ordered by the first facet they contain.
     pub fn split_islands(self) -> Vec<Self> {
-        let mut union_find = UnionFind::new(self.vertex_count() as usize);
+        let index_buffer = IndexBuffer::from_points(self.vertices());
+        let size = index_buffer.vertices().len() / 3;
 
-        for triangle in self.triangles() {
-            union_find.add_triangle(triangle);
+        self.split_islands_by(index_buffer.indices(), size)
+    }
+
+    /// Like [`StlFile::split_islands`], but facets are connected wherever
+    /// their vertices lie within `epsilon` of each other, as in
+    /// [`StlFile::weld_vertices`]
+    ///
+    /// Vertices keep their original positions.
+    pub fn split_islands_with_tolerance(self, epsilon: f32) -> Vec<Self> {
+        let welded = Welded::new(self.vertices(), epsilon);
+
+        self.split_islands_by(&welded.indices, welded.points.len())
+    }
+
+    /// Split into islands given the id of each vertex, all below `size`
+    fn split_islands_by(self, indices: &[u32], size: usize) -> Vec<Self> {
+        let mut union_find = UnionFind::new(size);
+
+        let facets = indices.chunks_exact(3);
+        for ((triangle, &attribute), ids) in self.triangles().zip(&self.attributes).zip(facets) {
+            let ids = [ids[0] as usize, ids[1] as usize, ids[2] as usize];
+            union_find.add_triangle(triangle, attribute, ids);
         }
 
         union_find.files()
     }
 }
 
+impl Default for StlFile {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Extend<Triangle> for StlFile {
+    fn extend<I: IntoIterator<Item = Triangle>>(&mut self, triangles: I) {
+        for triangle in triangles {
+            self.push_triangle(triangle);
+        }
+    }
+}
+
+impl FromIterator<Triangle> for StlFile {
+    fn from_iter<I: IntoIterator<Item = Triangle>>(triangles: I) -> Self {
+        Self::from_triangles(triangles)
+    }
+}
+
 /// Compressed representation of vertices that stores each vertex only once
 #[derive(Debug, Clone)]
 pub struct IndexBuffer {
@@ -224,6 +1199,12 @@ pub struct IndexBuffer {
     indices: Vec<u32>,
 }
 
+fn push_point(p: Point, vertices: &mut Vec<f32>) {
+    vertices.push(p.x);
+    vertices.push(p.y);
+    vertices.push(p.z);
+}
+
 fn push_vertex_only(v: VertexWithNormal, vertices: &mut Vec<f32>) {
     vertices.push(v.vertex.x);
     vertices.push(v.vertex.y);
@@ -241,9 +1222,9 @@ fn push_vertex_and_normal(v: VertexWithNormal, vertices: &mut Vec<f32>) {
 }
 
 impl IndexBuffer {
-    fn from_buffer(
-        buffer: impl Iterator<Item = VertexWithNormal>,
-        push: fn(VertexWithNormal, &mut Vec<f32>),
+    fn from_buffer<T: Copy + Hash + Eq>(
+        buffer: impl Iterator<Item = T>,
+        push: fn(T, &mut Vec<f32>),
     ) -> Self {
         let mut distinct_vertices = HashMap::new();
         let mut vertices = Vec::new();
@@ -267,6 +1248,11 @@ impl IndexBuffer {
         Self { vertices, indices }
     }
 
+    /// Deduplicate points alone, ignoring the normals of their facets
+    pub(crate) fn from_points(points: impl Iterator<Item = Point>) -> Self {
+        Self::from_buffer(points, push_point)
+    }
+
     pub fn vertices(&self) -> &[f32] {
         &self.vertices
     }
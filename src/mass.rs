@@ -0,0 +1,108 @@
+use crate::{math::Vec3, Point64, Triangle};
+
+/// Surface area, volume and inertia of a mesh, assuming uniform unit density
+///
+/// Volume is computed by summing the signed volumes of the tetrahedra formed
+/// by each facet and the origin. This is exact for closed meshes, regardless
+/// of where the origin lies. For open meshes, the facets do not enclose a
+/// region, so `signed_volume`, `centroid` and `inertia_tensor` depend on the
+/// position of the origin and are not meaningful.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MassProperties {
+    pub surface_area: f64,
+
+    /// Positive when facets are wound counter-clockwise when seen from
+    /// outside, as is required by the STL format, and negative when the mesh
+    /// is inside out
+    pub signed_volume: f64,
+
+    /// The center of mass, or `None` if the mesh encloses no volume
+    pub centroid: Option<Point64>,
+
+    /// The inertia tensor about the centroid, or `None` if the mesh encloses
+    /// no volume
+    ///
+    /// This is the same for a mesh and its inside out counterpart.
+    pub inertia_tensor: Option<[[f64; 3]; 3]>,
+}
+
+impl MassProperties {
+    pub(crate) fn new(triangles: impl Iterator<Item = Triangle>) -> Self {
+        let mut surface_area = 0.0;
+
+        // six times the signed volume, and the first and second moments of
+        // volume scaled to match
+        let mut volume6 = 0.0;
+        let mut moment = Vec3::new(0.0, 0.0, 0.0);
+        let mut covariance = [[0.0; 3]; 3];
+
+        for triangle in triangles {
+            let a = Vec3::from(triangle.v0);
+            let b = Vec3::from(triangle.v1);
+            let c = Vec3::from(triangle.v2);
+
+            surface_area += (b - a).cross(c - a).length() / 2.0;
+
+            let det = a.dot(b.cross(c));
+            let sum = a + b + c;
+
+            volume6 += det;
+            moment = moment + sum * det;
+
+            // the integral of `x_i * x_j` over the tetrahedron between the
+            // facet and the origin, multiplied by 120
+            let a = [a.x, a.y, a.z];
+            let b = [b.x, b.y, b.z];
+            let c = [c.x, c.y, c.z];
+            let sum = [sum.x, sum.y, sum.z];
+
+            for i in 0..3 {
+                for j in 0..3 {
+                    covariance[i][j] +=
+                        det * (a[i] * a[j] + b[i] * b[j] + c[i] * c[j] + sum[i] * sum[j]);
+                }
+            }
+        }
+
+        let signed_volume = volume6 / 6.0;
+
+        if signed_volume == 0.0 || !signed_volume.is_finite() {
+            return Self {
+                surface_area,
+                signed_volume,
+                centroid: None,
+                inertia_tensor: None,
+            };
+        }
+
+        let centroid = moment * (1.0 / (4.0 * volume6));
+        let centroid_arr = [centroid.x, centroid.y, centroid.z];
+
+        // move the second moment to the centroid, flipping its sign for
+        // inside out meshes
+        let mut c = [[0.0; 3]; 3];
+        for i in 0..3 {
+            for j in 0..3 {
+                c[i][j] = (covariance[i][j] / 120.0
+                    - signed_volume * centroid_arr[i] * centroid_arr[j])
+                    * signed_volume.signum();
+            }
+        }
+
+        let trace = c[0][0] + c[1][1] + c[2][2];
+
+        let mut inertia_tensor = [[0.0; 3]; 3];
+        for i in 0..3 {
+            for j in 0..3 {
+                inertia_tensor[i][j] = if i == j { trace - c[i][j] } else { -c[i][j] };
+            }
+        }
+
+        Self {
+            surface_area,
+            signed_volume,
+            centroid: Some(centroid.to_point64()),
+            inertia_tensor: Some(inertia_tensor),
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use std::ops::{Add, Mul, Sub};
+
+use crate::{Normal, Point, Point64, Triangle};
+
+/// Double precision vector used for geometric computations, so that
+/// accumulating over large meshes does not lose precision
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Vec3 {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl Vec3 {
+    pub fn new(x: f64, y: f64, z: f64) -> Self {
+        Self { x, y, z }
+    }
+
+    pub fn dot(self, other: Self) -> f64 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn cross(self, other: Self) -> Self {
+        Self {
+            x: self.y * other.z - self.z * other.y,
+            y: self.z * other.x - self.x * other.z,
+            z: self.x * other.y - self.y * other.x,
+        }
+    }
+
+    pub fn length(self) -> f64 {
+        self.dot(self).sqrt()
+    }
+
+    pub fn is_finite(self) -> bool {
+        self.x.is_finite() && self.y.is_finite() && self.z.is_finite()
+    }
+
+    /// This vector scaled to unit length, or `None` if it has zero length
+    pub fn normalized(self) -> Option<Self> {
+        let length = self.length();
+
+        if length > 0.0 && length.is_finite() {
+            Some(Self::new(self.x / length, self.y / length, self.z / length))
+        } else {
+            None
+        }
+    }
+
+    pub fn to_point64(self) -> Point64 {
+        Point64 {
+            x: self.x,
+            y: self.y,
+            z: self.z,
+        }
+    }
+
+    pub fn to_normal(self) -> Normal {
+        Normal {
+            i: self.x as f32,
+            j: self.y as f32,
+            k: self.z as f32,
+        }
+    }
+
+    /// The angle between two vectors in degrees
+    pub fn angle_degrees(self, other: Self) -> f64 {
+        let cos = self.dot(other) / (self.length() * other.length());
+
+        cos.clamp(-1.0, 1.0).acos().to_degrees()
+    }
+}
+
+/// The cross product of two edges of a triangle, following the winding of its
+/// vertices, or `None` if they are collinear or coincident
+pub(crate) fn winding(triangle: &Triangle) -> Option<Vec3> {
+    let v0 = Vec3::from(triangle.v0);
+    let e1 = Vec3::from(triangle.v1) - v0;
+    let e2 = Vec3::from(triangle.v2) - v0;
+
+    let cross = e1.cross(e2);
+
+    // relative to the edge lengths, so that the result does not depend on scale
+    if cross.length() > f64::from(f32::EPSILON) * e1.length() * e2.length() {
+        Some(cross)
+    } else {
+        None
+    }
+}
+
+impl From<Point> for Vec3 {
+    fn from(p: Point) -> Self {
+        Self::new(f64::from(p.x), f64::from(p.y), f64::from(p.z))
+    }
+}
+
+impl From<Normal> for Vec3 {
+    fn from(n: Normal) -> Self {
+        Self::new(f64::from(n.i), f64::from(n.j), f64::from(n.k))
+    }
+}
+
+impl Add for Vec3 {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl Sub for Vec3 {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl Mul<f64> for Vec3 {
+    type Output = Self;
+
+    fn mul(self, scale: f64) -> Self {
+        Self::new(self.x * scale, self.y * scale, self.z * scale)
+    }
+}
@@ -0,0 +1,184 @@
+use std::io::Read;
+
+use crate::{LimitExceeded, StlResult};
+
+/// Controls how input is validated while parsing
+///
+/// No resource limits are enforced by default. When parsing untrusted input,
+/// set them to bound the work done and memory allocated; exceeding one returns
+/// [`StlError::LimitExceeded`](crate::StlError::LimitExceeded).
+///
+/// ```
+/// # use stl::{ParseOptions, ParseWarning, StlFile};
+/// let input = b"SOLID
+/// FACET NORMAL 0 0 1
+///   OUTER LOOP
+///     VERTEX 0 0 0
+///     VERTEX 1 0 0
+///     VERTEX 1 1 0
+///     VERTEX 0 1 0
+///   ENDLOOP
+/// ENDFACET
+/// ENDSOLID";
+///
+/// assert!(StlFile::parse(input).is_err());
+///
+/// let (stl, warnings) = StlFile::parse_with_options(input, &ParseOptions::lenient()).unwrap();
+///
+/// assert_eq!(stl.facet_count(), 2);
+/// assert!(warnings.contains(&ParseWarning::MissingName { offset: 5 }));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Require input to follow the STL grammar exactly
+    ///
+    /// When `false`, ASCII keywords are matched case-insensitively, the name
+    /// of a solid may be missing or contain spaces, `solid` may be followed by
+    /// any whitespace, and loops with more than three vertices are split into
+    /// triangles. Binary files declaring more facets than they contain are
+    /// parsed up to the last complete facet. Each of these is reported as a
+    /// [`ParseWarning`](crate::ParseWarning).
+    pub strict: bool,
+
+    /// Replace every stored normal with one computed from the winding of its
+    /// vertices, as in [`StlFile::recompute_normals`](crate::StlFile::recompute_normals)
+    pub recompute_normals: bool,
+
+    /// Largest number of facets to parse
+    pub max_facets: Option<u32>,
+
+    /// Largest input to accept, in bytes
+    ///
+    /// For compressed input, this also limits the total decompressed size of
+    /// every STL within it, as in [`StlFile::from_path_with_options`](crate::StlFile::from_path_with_options).
+    pub max_input_len: Option<usize>,
+
+    /// Largest parsed mesh to allocate, in bytes
+    ///
+    /// Each facet occupies [`ParseOptions::FACET_SIZE`] bytes once parsed.
+    /// The mesh is allocated up front for binary input, and grown no larger
+    /// than this limit for ASCII input.
+    /// This has no effect on [`StlReader`](crate::StlReader), which uses a
+    /// fixed amount of memory.
+    pub max_allocation: Option<usize>,
+}
+
+impl ParseOptions {
+    /// Memory occupied by a single parsed facet: its normal, three vertices
+    /// and attribute
+    pub const FACET_SIZE: usize = 4 * 3 + 4 * 9 + 2;
+
+    /// Options accepting common deviations from the STL grammar
+    pub fn lenient() -> Self {
+        Self {
+            strict: false,
+            ..Self::default()
+        }
+    }
+
+    pub(crate) fn check_input_len(&self, len: usize) -> Result<(), LimitExceeded> {
+        match self.max_input_len {
+            Some(limit) if len > limit => Err(LimitExceeded::InputLen { limit }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Read `reader` to the end, failing once more than `max_input_len` bytes
+    /// have been read rather than reading it all into memory
+    pub(crate) fn read_to_end(&self, reader: impl Read) -> StlResult<Vec<u8>> {
+        let mut buffer = Vec::new();
+
+        match self.max_input_len {
+            Some(limit) => {
+                let len = limit.saturating_add(1) as u64;
+                reader.take(len).read_to_end(&mut buffer)?;
+
+                self.check_input_len(buffer.len())?;
+            }
+            None => {
+                let mut reader = reader;
+                reader.read_to_end(&mut buffer)?;
+            }
+        }
+
+        Ok(buffer)
+    }
+
+    /// Check that `count` facets may be parsed
+    pub(crate) fn check_facet_count(&self, count: usize) -> Result<(), LimitExceeded> {
+        if let Some(limit) = self.max_facets {
+            if count > limit as usize {
+                return Err(LimitExceeded::FacetCount { limit });
+            }
+        }
+
+        if let Some(limit) = self.max_allocation {
+            if count.saturating_mul(Self::FACET_SIZE) > limit {
+                return Err(LimitExceeded::Allocation { limit });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The largest number of facets allowed by `max_facets` and
+    /// `max_allocation`, if either is set
+    pub(crate) fn facet_budget(&self) -> Option<usize> {
+        let facets = self.max_facets.map(|limit| limit as usize);
+        let allocation = self.max_allocation.map(|limit| limit / Self::FACET_SIZE);
+
+        match (facets, allocation) {
+            (Some(facets), Some(allocation)) => Some(facets.min(allocation)),
+            (facets, allocation) => facets.or(allocation),
+        }
+    }
+
+    /// These options with the facet limits reduced by `facets` that have
+    /// already been parsed, as for the later entries of an archive
+    #[cfg(feature = "compression")]
+    pub(crate) fn after_facets(&self, facets: usize) -> Self {
+        Self {
+            max_facets: self
+                .max_facets
+                .map(|limit| limit.saturating_sub(facets.min(u32::MAX as usize) as u32)),
+            max_allocation: self
+                .max_allocation
+                .map(|limit| limit.saturating_sub(facets.saturating_mul(Self::FACET_SIZE))),
+            ..*self
+        }
+    }
+
+    /// Report an error from parsing with reduced limits, such as those of
+    /// [`ParseOptions::after_facets`], against the limits of these options
+    #[cfg(feature = "compression")]
+    pub(crate) fn restore_limit(&self, err: crate::StlError) -> crate::StlError {
+        use crate::StlError;
+
+        let restored = match err {
+            StlError::LimitExceeded(LimitExceeded::InputLen { .. }) => self
+                .max_input_len
+                .map(|limit| LimitExceeded::InputLen { limit }),
+            StlError::LimitExceeded(LimitExceeded::FacetCount { .. }) => self
+                .max_facets
+                .map(|limit| LimitExceeded::FacetCount { limit }),
+            StlError::LimitExceeded(LimitExceeded::Allocation { .. }) => self
+                .max_allocation
+                .map(|limit| LimitExceeded::Allocation { limit }),
+            _ => None,
+        };
+
+        restored.map_or(err, StlError::LimitExceeded)
+    }
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            strict: true,
+            recompute_normals: false,
+            max_facets: None,
+            max_input_len: None,
+            max_allocation: None,
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use std::collections::VecDeque;
+
+use crate::{
+    adjacency::{facet_edges, Adjacency},
+    math::Vec3,
+};
+
+/// Which facets must have their winding reversed so that every facet winds
+/// consistently with its neighbors, and each connected component encloses a
+/// positive volume
+///
+/// Winding is propagated across edges shared by exactly two facets. Only
+/// closed components, where every edge is shared by exactly two facets, are
+/// flipped to enclose a positive volume. The volume of an open sheet depends
+/// on where the origin lies, so open components are only made consistent.
+pub(crate) fn orientation_flips(adjacency: &Adjacency) -> Vec<bool> {
+    let num_of_facets = adjacency.facets.len();
+
+    let mut flips: Vec<Option<bool>> = vec![None; num_of_facets];
+    let mut queue = VecDeque::new();
+
+    for start in 0..num_of_facets {
+        if flips[start].is_some() {
+            continue;
+        }
+
+        flips[start] = Some(false);
+        queue.push_back(start);
+
+        let mut component = Vec::new();
+        let mut closed = true;
+
+        while let Some(facet) = queue.pop_front() {
+            component.push(facet);
+
+            let flipped = flips[facet] == Some(true);
+
+            let num_of_edges = facet_edges(adjacency.facets[facet])
+                .iter()
+                .filter(|(a, b)| a != b)
+                .count();
+
+            closed &= adjacency.manifold_neighbors(facet).count() == num_of_edges;
+
+            for (neighbor, same_direction) in adjacency.manifold_neighbors(facet) {
+                if flips[neighbor].is_none() {
+                    // neighbors must traverse a shared edge in opposite directions
+                    flips[neighbor] = Some(flipped ^ same_direction);
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+
+        if !closed {
+            continue;
+        }
+
+        let volume: f64 = component
+            .iter()
+            .map(|&facet| {
+                let volume = signed_volume(adjacency, adjacency.facets[facet]);
+
+                if flips[facet] == Some(true) {
+                    -volume
+                } else {
+                    volume
+                }
+            })
+            .sum();
+
+        if volume < 0.0 {
+            for facet in component {
+                flips[facet] = flips[facet].map(|flip| !flip);
+            }
+        }
+    }
+
+    flips.into_iter().map(|flip| flip == Some(true)).collect()
+}
+
+/// Six times the signed volume of the tetrahedron between a facet and the origin
+fn signed_volume(adjacency: &Adjacency, [a, b, c]: [usize; 3]) -> f64 {
+    let v0 = Vec3::from(adjacency.points[a]);
+    let v1 = Vec3::from(adjacency.points[b]);
+    let v2 = Vec3::from(adjacency.points[c]);
+
+    v0.dot(v1.cross(v2))
+}
@@ -0,0 +1,551 @@
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, Chain, Cursor, Read, Take},
+    path::Path,
+};
+
+use crate::{
+    binary::{decode_facet, FACET_LEN, HEADER_LEN},
+    error::{Location, ParseError, ParseErrorKind},
+    LimitExceeded, Normal, ParseOptions, Point, StlFormat, StlResult, Triangle,
+};
+
+/// Number of bytes read up front to determine the format of the input
+const SNIFF_LEN: usize = 1024;
+
+/// Longest ASCII token kept in memory. Longer tokens are truncated, which
+/// makes them fail to match any keyword or parse as a float
+const MAX_TOKEN_LEN: usize = 256;
+
+/// Longest prefix of the current line kept for error snippets
+const MAX_SNIPPET_LEN: usize = 80;
+
+type Input<R> = BufReader<Chain<Cursor<Vec<u8>>, Take<R>>>;
+
+/// Reads triangles one at a time from any [`Read`] implementation
+///
+/// Unlike [`StlFile::parse`](crate::StlFile::parse), only a small, fixed-size
+/// buffer is held in memory regardless of the size of the input. Both ASCII
+/// and binary input are supported; the format is detected from the first
+/// kilobyte of input.
+///
+/// Iteration stops after the first error. Resource limits may be set with
+/// [`StlReader::with_options`].
+///
+/// ```
+/// # use stl::StlReader;
+/// let input: &[u8] = b"solid foo
+/// facet normal 0 0 1 outer loop vertex 0 0 0 vertex 1 0 0 vertex 0 1 0 endloop endfacet
+/// endsolid foo";
+///
+/// let reader = StlReader::new(input).unwrap();
+/// let triangles = reader.collect::<Result<Vec<_>, _>>().unwrap();
+///
+/// assert_eq!(triangles.len(), 1);
+/// ```
+#[derive(Debug)]
+pub struct StlReader<R: Read> {
+    input: Input<R>,
+    state: State,
+    header: [u8; 80],
+    name: String,
+    options: ParseOptions,
+    num_of_facets: usize,
+    done: bool,
+}
+
+#[derive(Debug)]
+enum State {
+    Ascii(Tokenizer),
+    Binary {
+        offset: usize,
+        facet: u32,
+        num_of_facets: u32,
+    },
+}
+
+impl StlReader<File> {
+    /// Open a file on disk for streaming
+    pub fn open(path: impl AsRef<Path>) -> StlResult<Self> {
+        Self::new(File::open(path)?)
+    }
+}
+
+impl<R: Read> StlReader<R> {
+    /// Detect the format of `reader` and read its header
+    pub fn new(reader: R) -> StlResult<Self> {
+        Self::with_options(reader, ParseOptions::default())
+    }
+
+    /// Detect the format of `reader` and read its header, enforcing the
+    /// limits in `options`
+    ///
+    /// Input is always parsed strictly, and `max_allocation` is ignored as
+    /// only a fixed-size buffer is held in memory. Normals are recomputed if
+    /// `recompute_normals` is set.
+    pub fn with_options(mut reader: R, options: ParseOptions) -> StlResult<Self> {
+        let options = ParseOptions {
+            max_allocation: None,
+            ..options
+        };
+
+        let mut head = vec![0; SNIFF_LEN];
+        let len = read_fully(&mut reader, &mut head)?;
+        head.truncate(len);
+
+        options.check_input_len(len)?;
+
+        let format = if len < SNIFF_LEN {
+            StlFormat::detect(&head)
+        } else {
+            StlFormat::detect_prefix(&head)
+        };
+
+        // read at most one byte past the limit, so that exceeding it can be detected
+        let remaining = options
+            .max_input_len
+            .map_or(u64::MAX, |limit| (limit - len) as u64 + 1);
+
+        let mut input = BufReader::new(Cursor::new(head).chain(reader.take(remaining)));
+
+        let mut header = [0; 80];
+        let mut name = String::new();
+
+        let state = Self::read_start(format, &mut input, &mut header, &mut name);
+        check_input_len(&input, &options)?;
+        let state = state?;
+
+        if let State::Binary { num_of_facets, .. } = state {
+            options.check_facet_count(num_of_facets as usize)?;
+        }
+
+        Ok(Self {
+            input,
+            state,
+            header,
+            name,
+            options,
+            num_of_facets: 0,
+            done: false,
+        })
+    }
+
+    /// Read the binary header, or the ASCII `solid` keyword and name
+    fn read_start(
+        format: StlFormat,
+        input: &mut Input<R>,
+        header: &mut [u8; 80],
+        name: &mut String,
+    ) -> StlResult<State> {
+        Ok(match format {
+            StlFormat::Ascii => {
+                let mut tokenizer = Tokenizer::new();
+                tokenizer.expect(input, "solid")?;
+                *name = tokenizer.read_name(input)?;
+
+                State::Ascii(tokenizer)
+            }
+            StlFormat::Binary => {
+                let mut num_of_facets = [0; 4];
+
+                if read_fully(input, header)? < header.len()
+                    || read_fully(input, &mut num_of_facets)? < num_of_facets.len()
+                {
+                    return Err(ParseError::new(ParseErrorKind::TruncatedHeader, 0).into());
+                }
+
+                State::Binary {
+                    offset: HEADER_LEN,
+                    facet: 0,
+                    num_of_facets: u32::from_le_bytes(num_of_facets),
+                }
+            }
+        })
+    }
+
+    /// The 80-byte header of binary input, or all zeros for ASCII input
+    pub fn header(&self) -> &[u8; 80] {
+        &self.header
+    }
+
+    /// The name of the solid in ASCII input, or an empty string for binary input
+    ///
+    /// When ASCII input contains multiple solids, this is the name of the solid
+    /// containing the most recently read triangle.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The format of the underlying input
+    pub fn format(&self) -> StlFormat {
+        match self.state {
+            State::Ascii(..) => StlFormat::Ascii,
+            State::Binary { .. } => StlFormat::Binary,
+        }
+    }
+
+    /// Read the next triangle along with its attribute bytes, which are zero
+    /// for ASCII input
+    ///
+    /// This advances the same iteration as [`Iterator::next`].
+    pub fn next_with_attribute(&mut self) -> Option<StlResult<(Triangle, u16)>> {
+        if self.done {
+            return None;
+        }
+
+        match self.read_checked_triangle() {
+            Ok(Some(triangle)) => Some(Ok(triangle)),
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(err) => {
+                self.done = true;
+                Some(Err(err))
+            }
+        }
+    }
+
+    /// Iterate over triangles along with their attribute bytes, so that
+    /// colors survive streaming into an [`StlWriter`](crate::StlWriter)
+    pub fn with_attributes(mut self) -> impl Iterator<Item = StlResult<(Triangle, u16)>> {
+        std::iter::from_fn(move || self.next_with_attribute())
+    }
+
+    fn read_triangle(&mut self) -> StlResult<Option<(Triangle, u16)>> {
+        match &mut self.state {
+            State::Ascii(tokenizer) => Ok(tokenizer
+                .read_triangle(&mut self.input, &mut self.name)?
+                .map(|triangle| (triangle, 0))),
+            State::Binary {
+                offset,
+                facet,
+                num_of_facets,
+            } => {
+                if facet == num_of_facets {
+                    return Ok(None);
+                }
+
+                let mut record = [0; FACET_LEN];
+                let len = read_fully(&mut self.input, &mut record)?;
+
+                if len < record.len() {
+                    let kind = if len == 0 {
+                        ParseErrorKind::FacetCountMismatch {
+                            expected: *num_of_facets,
+                            found: *facet,
+                        }
+                    } else {
+                        ParseErrorKind::TruncatedFacet { facet: *facet }
+                    };
+
+                    return Err(ParseError::new(kind, *offset).into());
+                }
+
+                *offset += record.len();
+                *facet += 1;
+
+                Ok(Some(decode_facet(&record)))
+            }
+        }
+    }
+
+    /// Read the next triangle, reporting an exceeded limit in place of
+    /// whatever else happened
+    fn read_checked_triangle(&mut self) -> StlResult<Option<(Triangle, u16)>> {
+        let triangle = self.read_triangle();
+        check_input_len(&self.input, &self.options)?;
+        let mut triangle = triangle?;
+
+        if self.options.recompute_normals {
+            triangle =
+                triangle.map(|(triangle, attribute)| (triangle.with_computed_normal(), attribute));
+        }
+
+        if triangle.is_some() {
+            self.num_of_facets += 1;
+            self.options.check_facet_count(self.num_of_facets)?;
+        }
+
+        Ok(triangle)
+    }
+}
+
+impl<R: Read> Iterator for StlReader<R> {
+    type Item = StlResult<Triangle>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_with_attribute()
+            .map(|result| result.map(|(triangle, _)| triangle))
+    }
+}
+
+/// Fail if more than `max_input_len` bytes have been read from `input`
+///
+/// As the underlying reader is limited to one byte past the limit, this is
+/// the case exactly when that byte has been read.
+fn check_input_len<R: Read>(input: &Input<R>, options: &ParseOptions) -> StlResult<()> {
+    match options.max_input_len {
+        Some(limit) if input.get_ref().get_ref().1.limit() == 0 => {
+            Err(LimitExceeded::InputLen { limit }.into())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Fill as much of `buffer` as possible, returning the number of bytes read
+fn read_fully(reader: &mut impl Read, buffer: &mut [u8]) -> io::Result<usize> {
+    let mut len = 0;
+
+    while len < buffer.len() {
+        match reader.read(&mut buffer[len..]) {
+            Ok(0) => break,
+            Ok(n) => len += n,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+
+    Ok(len)
+}
+
+/// Splits ASCII input into whitespace-delimited tokens while tracking their
+/// position for error reporting
+#[derive(Debug)]
+struct Tokenizer {
+    token: Vec<u8>,
+    token_offset: usize,
+    token_line: usize,
+    token_column: usize,
+    offset: usize,
+    line: usize,
+    column: usize,
+    current_line: Vec<u8>,
+}
+
+impl Tokenizer {
+    fn new() -> Self {
+        Self {
+            token: Vec::with_capacity(MAX_TOKEN_LEN),
+            token_offset: 0,
+            token_line: 1,
+            token_column: 1,
+            offset: 0,
+            line: 1,
+            column: 1,
+            current_line: Vec::with_capacity(MAX_SNIPPET_LEN),
+        }
+    }
+
+    fn advance(&mut self, b: u8) {
+        self.offset += 1;
+
+        if b == b'\n' {
+            self.line += 1;
+            self.column = 1;
+            self.current_line.clear();
+        } else {
+            self.column += 1;
+
+            if self.current_line.len() < MAX_SNIPPET_LEN {
+                self.current_line.push(b);
+            }
+        }
+    }
+
+    /// Read the next token into `self.token`, returning `false` at the end of input
+    fn next_token(&mut self, input: &mut impl BufRead) -> io::Result<bool> {
+        self.token.clear();
+
+        let mut in_token = false;
+
+        loop {
+            let buffer = input.fill_buf()?;
+
+            if buffer.is_empty() {
+                return Ok(in_token);
+            }
+
+            let mut used = 0;
+            let mut finished = false;
+
+            for &b in buffer {
+                if b.is_ascii_whitespace() {
+                    if in_token {
+                        finished = true;
+                        break;
+                    }
+                } else {
+                    if !in_token {
+                        in_token = true;
+                        self.token_offset = self.offset;
+                        self.token_line = self.line;
+                        self.token_column = self.column;
+                    }
+
+                    if self.token.len() < MAX_TOKEN_LEN {
+                        self.token.push(b);
+                    }
+                }
+
+                used += 1;
+                self.advance(b);
+            }
+
+            input.consume(used);
+
+            if finished {
+                return Ok(true);
+            }
+        }
+    }
+
+    /// Read the name following `solid`, which is empty if the line ends first
+    fn read_name(&mut self, input: &mut impl BufRead) -> io::Result<String> {
+        loop {
+            match input.fill_buf()?.first() {
+                Some(&b) if b == b' ' || b == b'\t' => {
+                    self.advance(b);
+                    input.consume(1);
+                }
+                Some(b) if !b.is_ascii_whitespace() => {
+                    self.next_token(input)?;
+                    return Ok(self.found());
+                }
+                _ => return Ok(String::new()),
+            }
+        }
+    }
+
+    fn error(&self, kind: ParseErrorKind) -> ParseError {
+        ParseError::new(kind, self.token_offset).with_location(Location {
+            line: self.token_line,
+            column: self.token_column,
+            snippet: String::from_utf8_lossy(&self.current_line)
+                .trim()
+                .to_owned(),
+        })
+    }
+
+    fn eof_error(&self) -> ParseError {
+        ParseError::new(ParseErrorKind::UnexpectedEof, self.offset).with_location(Location {
+            line: self.line,
+            column: self.column,
+            snippet: String::from_utf8_lossy(&self.current_line)
+                .trim()
+                .to_owned(),
+        })
+    }
+
+    fn found(&self) -> String {
+        String::from_utf8_lossy(&self.token).into_owned()
+    }
+
+    fn expect(&mut self, input: &mut impl BufRead, keyword: &'static str) -> StlResult<()> {
+        if !self.next_token(input)? {
+            return Err(self.eof_error().into());
+        }
+
+        if self.token != keyword.as_bytes() {
+            return Err(self
+                .error(ParseErrorKind::ExpectedKeyword {
+                    expected: keyword,
+                    found: self.found(),
+                })
+                .into());
+        }
+
+        Ok(())
+    }
+
+    fn read_float(&mut self, input: &mut impl BufRead) -> StlResult<f32> {
+        if !self.next_token(input)? {
+            return Err(self.eof_error().into());
+        }
+
+        fast_float::parse(&self.token).map_err(|_| {
+            self.error(ParseErrorKind::InvalidFloat {
+                found: self.found(),
+            })
+            .into()
+        })
+    }
+
+    fn read_point(&mut self, input: &mut impl BufRead) -> StlResult<Point> {
+        self.expect(input, "vertex")?;
+
+        Ok(Point {
+            x: self.read_float(input)?,
+            y: self.read_float(input)?,
+            z: self.read_float(input)?,
+        })
+    }
+
+    /// Skip the optional name following `endsolid` and, if another solid
+    /// follows, read its name. Anything else after the last solid is ignored
+    fn next_solid(&mut self, input: &mut impl BufRead, name: &mut String) -> StlResult<bool> {
+        if !self.next_token(input)? {
+            return Ok(false);
+        }
+
+        if self.token != b"solid" && !self.next_token(input)? {
+            return Ok(false);
+        }
+
+        if self.token != b"solid" {
+            return Ok(false);
+        }
+
+        *name = self.read_name(input)?;
+
+        Ok(true)
+    }
+
+    fn read_triangle(
+        &mut self,
+        input: &mut impl BufRead,
+        name: &mut String,
+    ) -> StlResult<Option<Triangle>> {
+        loop {
+            if !self.next_token(input)? {
+                return Err(self.eof_error().into());
+            }
+
+            match self.token.as_slice() {
+                b"endsolid" => {
+                    if !self.next_solid(input, name)? {
+                        return Ok(None);
+                    }
+                }
+                b"facet" => break,
+                _ => {
+                    return Err(self
+                        .error(ParseErrorKind::UnexpectedToken {
+                            found: self.found(),
+                        })
+                        .into())
+                }
+            }
+        }
+
+        self.expect(input, "normal")?;
+
+        let normal = Normal {
+            i: self.read_float(input)?,
+            j: self.read_float(input)?,
+            k: self.read_float(input)?,
+        };
+
+        self.expect(input, "outer")?;
+        self.expect(input, "loop")?;
+
+        let v0 = self.read_point(input)?;
+        let v1 = self.read_point(input)?;
+        let v2 = self.read_point(input)?;
+
+        self.expect(input, "endloop")?;
+        self.expect(input, "endfacet")?;
+
+        Ok(Some(Triangle { normal, v0, v1, v2 }))
+    }
+}
@@ -0,0 +1,156 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    adjacency::{facet_edges, Adjacency, Edge},
+    Point,
+};
+
+/// Defects preventing a mesh from being a closed, consistently wound surface
+///
+/// Vertices are welded wherever their [`Point`]s compare equal. Edges are
+/// reported as the pair of points they join, in the order they first appear
+/// in the mesh.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TopologyReport {
+    /// Edges used by only one facet, bordering a hole
+    pub boundary_edges: Vec<(Point, Point)>,
+
+    /// Edges used by more than two facets
+    pub non_manifold_edges: Vec<(Point, Point)>,
+
+    /// Vertices where facets meet that are not connected to each other
+    /// through edges around the vertex, such as the tip shared by two cones
+    pub non_manifold_vertices: Vec<Point>,
+
+    /// Edges used by two facets which traverse it in the same direction, so
+    /// that one of them is wound the wrong way
+    pub inconsistent_edges: Vec<(Point, Point)>,
+}
+
+impl TopologyReport {
+    pub(crate) fn new(adjacency: &Adjacency) -> Self {
+        // visit each edge at its first use, following the winding of each facet
+        let mut seen = HashSet::new();
+        let edges = adjacency
+            .facets
+            .iter()
+            .flat_map(|&vertices| IntoIterator::into_iter(facet_edges(vertices)))
+            .filter(|&(a, b)| a != b)
+            .map(|(a, b)| (a.min(b), a.max(b)))
+            .filter(|&edge| seen.insert(edge))
+            .map(|edge| (edge, &adjacency.edges[&edge]));
+
+        let point_pair = |&(a, b): &Edge| (adjacency.points[a], adjacency.points[b]);
+
+        let mut boundary_edges = Vec::new();
+        let mut non_manifold_edges = Vec::new();
+        let mut inconsistent_edges = Vec::new();
+
+        for (edge, facets) in edges {
+            match facets.as_slice() {
+                [_] => boundary_edges.push(point_pair(&edge)),
+                [(_, d0), (_, d1)] if d0 == d1 => inconsistent_edges.push(point_pair(&edge)),
+                [_, _] => {}
+                _ => non_manifold_edges.push(point_pair(&edge)),
+            }
+        }
+
+        let non_manifold_vertices = non_manifold_vertices(adjacency)
+            .into_iter()
+            .map(|vertex| adjacency.points[vertex])
+            .collect();
+
+        Self {
+            boundary_edges,
+            non_manifold_edges,
+            non_manifold_vertices,
+            inconsistent_edges,
+        }
+    }
+
+    /// Whether the mesh is a closed manifold, with every edge shared by
+    /// exactly two facets and a single fan of facets around every vertex
+    ///
+    /// Winding is not considered; see [`TopologyReport::is_consistently_oriented`].
+    pub fn is_watertight(&self) -> bool {
+        self.boundary_edges.is_empty()
+            && self.non_manifold_edges.is_empty()
+            && self.non_manifold_vertices.is_empty()
+    }
+
+    /// Whether every edge shared by two facets is traversed in opposite
+    /// directions by them
+    pub fn is_consistently_oriented(&self) -> bool {
+        self.inconsistent_edges.is_empty()
+    }
+}
+
+/// Vertices whose incident facets form more than one group connected by
+/// edges containing the vertex, in ascending order
+fn non_manifold_vertices(adjacency: &Adjacency) -> Vec<usize> {
+    let mut incident: HashMap<usize, Vec<usize>> = HashMap::new();
+
+    for (facet, vertices) in adjacency.facets.iter().enumerate() {
+        for &vertex in vertices {
+            let facets = incident.entry(vertex).or_default();
+
+            // degenerate facets may use a vertex more than once
+            if facets.last() != Some(&facet) {
+                facets.push(facet);
+            }
+        }
+    }
+
+    let mut vertices = incident
+        .into_iter()
+        .filter(|(vertex, facets)| count_fans(adjacency, *vertex, facets) > 1)
+        .map(|(vertex, _)| vertex)
+        .collect::<Vec<_>>();
+
+    vertices.sort_unstable();
+
+    vertices
+}
+
+/// The number of groups of `facets` around `vertex` connected through edges
+/// which contain `vertex`
+fn count_fans(adjacency: &Adjacency, vertex: usize, facets: &[usize]) -> usize {
+    let mut parent = (0..facets.len()).collect::<Vec<_>>();
+
+    // the facets around `vertex` sharing each edge out of it
+    let mut spokes: HashMap<usize, usize> = HashMap::new();
+
+    for (idx, &facet) in facets.iter().enumerate() {
+        for &(a, b) in &facet_edges(adjacency.facets[facet]) {
+            let other = match (a == vertex, b == vertex) {
+                (true, false) => b,
+                (false, true) => a,
+                _ => continue,
+            };
+
+            match spokes.get(&other) {
+                Some(&first) => {
+                    let (r0, r1) = (root(&mut parent, first), root(&mut parent, idx));
+                    parent[r0] = r1;
+                }
+                None => {
+                    spokes.insert(other, idx);
+                }
+            }
+        }
+    }
+
+    (0..facets.len())
+        .filter(|&idx| root(&mut parent, idx) == idx)
+        .count()
+}
+
+/// The representative of the group containing `idx`, halving paths as it goes
+fn root(parent: &mut [usize], mut idx: usize) -> usize {
+    while parent[idx] != idx {
+        parent[idx] = parent[parent[idx]];
+        idx = parent[idx];
+    }
+
+    idx
+}
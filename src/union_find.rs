@@ -1,67 +1,57 @@
 use std::collections::{hash_map::Entry, HashMap};
 
-use crate::{Point, StlFile, Triangle};
+use crate::{StlFile, Triangle};
 
+/// Connected components of facets, joined wherever they share a vertex id
 pub(crate) struct UnionFind {
     ids: Vec<usize>,
-    elements: HashMap<Point, usize>,
     number_of_elements: usize,
-    triangles: Vec<Triangle>,
+    triangles: Vec<(Triangle, u16, usize)>,
 }
 
 impl UnionFind {
+    /// Vertex ids passed to [`UnionFind::add_triangle`] must be below `size`
     pub fn new(size: usize) -> Self {
         Self {
             ids: (0..size).collect(),
-            elements: HashMap::new(),
-            number_of_elements: 0,
+            number_of_elements: size,
             triangles: Vec::new(),
         }
     }
 
+    /// One file per connected component, ordered by their first triangle
     pub fn files(&mut self) -> Vec<StlFile> {
-        let mut map: HashMap<_, StlFile> = HashMap::new();
+        let mut indices: HashMap<usize, usize> = HashMap::new();
+        let mut files: Vec<StlFile> = Vec::new();
 
-        for triangle in self.triangles.clone() {
-            let root = self.root(triangle.v0);
+        for (triangle, attribute, id) in self.triangles.clone() {
+            let root = self.root(id);
 
-            match map.entry(root) {
-                Entry::Occupied(mut val) => {
-                    val.get_mut().add_triangle(triangle);
+            match indices.entry(root) {
+                Entry::Occupied(val) => {
+                    files[*val.get()].push_triangle_with_attribute(triangle, attribute);
                 }
                 Entry::Vacant(ptr) => {
                     let mut file = StlFile::new();
-                    file.add_triangle(triangle);
-                    ptr.insert(file);
+                    file.push_triangle_with_attribute(triangle, attribute);
+
+                    ptr.insert(files.len());
+                    files.push(file);
                 }
             }
         }
 
-        map.into_iter().map(|(_, v)| v).collect()
+        files
     }
 
-    pub fn add_triangle(&mut self, triangle: Triangle) {
-        self.connect_points(triangle.v0, triangle.v1);
-        self.connect_points(triangle.v0, triangle.v2);
-
-        self.triangles.push(triangle);
-    }
+    pub fn add_triangle(&mut self, triangle: Triangle, attribute: u16, [a, b, c]: [usize; 3]) {
+        self.connect(a, b);
+        self.connect(a, c);
 
-    fn get_id_for_point(&mut self, point: Point) -> usize {
-        let idx = self.elements.len();
-        match self.elements.entry(point) {
-            Entry::Occupied(val) => *val.get(),
-            Entry::Vacant(ptr) => {
-                ptr.insert(idx);
-                self.number_of_elements += 1;
-                idx
-            }
-        }
+        self.triangles.push((triangle, attribute, a));
     }
 
-    fn root(&mut self, point: Point) -> usize {
-        let mut id = self.get_id_for_point(point);
-
+    fn root(&mut self, mut id: usize) -> usize {
         let mut root = id;
 
         while root != self.ids[root] {
@@ -70,23 +60,23 @@ impl UnionFind {
 
         while root != id {
             let next = self.ids[id];
-            self.ids[next] = root;
+            self.ids[id] = root;
             id = next;
         }
 
         root
     }
 
-    fn connect_points(&mut self, p1: Point, p2: Point) {
-        let root_p1 = self.root(p1);
-        let root_p2 = self.root(p2);
+    fn connect(&mut self, id1: usize, id2: usize) {
+        let root1 = self.root(id1);
+        let root2 = self.root(id2);
 
-        if root_p1 == root_p2 {
+        if root1 == root2 {
             return;
         }
 
         self.number_of_elements -= 1;
 
-        self.ids[root_p1] = root_p2;
+        self.ids[root1] = root2;
     }
 }
@@ -0,0 +1,85 @@
+use crate::{
+    math::{winding, Vec3},
+    ParseWarning, Triangle,
+};
+
+/// A facet whose stored normal disagrees with the winding of its vertices
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NormalDeviation {
+    pub facet: u32,
+
+    /// The angle in degrees between the stored normal and the normal implied
+    /// by the winding, or `None` if the stored normal is zero or not finite
+    pub angle: Option<f32>,
+}
+
+pub(crate) fn normal_deviations(
+    triangles: impl Iterator<Item = Triangle>,
+    threshold: f32,
+) -> Vec<NormalDeviation> {
+    triangles
+        .enumerate()
+        .filter_map(|(facet, triangle)| {
+            let winding = winding(&triangle).filter(|w| w.is_finite())?;
+            let normal = Vec3::from(triangle.normal);
+
+            let angle = if normal.length() > 0.0 && normal.is_finite() {
+                let angle = normal.angle_degrees(winding) as f32;
+
+                if angle <= threshold {
+                    return None;
+                }
+
+                Some(angle)
+            } else {
+                None
+            };
+
+            Some(NormalDeviation {
+                facet: facet as u32,
+                angle,
+            })
+        })
+        .collect()
+}
+
+/// Check each facet for non-finite coordinates, zero area, and a stored
+/// normal which disagrees with its winding
+pub(crate) fn validate(triangles: impl Iterator<Item = Triangle>) -> Vec<ParseWarning> {
+    let mut warnings = Vec::new();
+
+    for (facet, triangle) in triangles.enumerate() {
+        let facet = facet as u32;
+
+        let normal = Vec3::from(triangle.normal);
+        let v0 = Vec3::from(triangle.v0);
+        let v1 = Vec3::from(triangle.v1);
+        let v2 = Vec3::from(triangle.v2);
+
+        if ![normal, v0, v1, v2].iter().all(|v| v.is_finite()) {
+            warnings.push(ParseWarning::NonFinite { facet });
+            continue;
+        }
+
+        let winding = match winding(&triangle) {
+            Some(winding) => winding,
+            None => {
+                warnings.push(ParseWarning::ZeroArea { facet });
+                continue;
+            }
+        };
+
+        // many exporters write a zero normal and leave it to the reader
+        if normal.length() == 0.0 {
+            continue;
+        }
+
+        let angle = normal.angle_degrees(winding) as f32;
+
+        if angle > ParseWarning::NORMAL_TOLERANCE {
+            warnings.push(ParseWarning::NormalMismatch { facet, angle });
+        }
+    }
+
+    warnings
+}
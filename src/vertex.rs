@@ -1,8 +1,11 @@
 use std::{
     cmp,
     hash::{Hash, Hasher},
+    ops::{Deref, DerefMut},
 };
 
+use crate::math::{self, Vec3};
+
 /// A 3d vector perpendicular to a facet
 #[derive(Debug, Clone, Copy)]
 pub struct Normal {
@@ -43,6 +46,40 @@ pub struct Triangle {
     pub v2: Point,
 }
 
+impl Triangle {
+    /// The unit normal implied by the counter-clockwise winding of the
+    /// vertices, or `None` if they are collinear or coincident
+    pub fn computed_normal(&self) -> Option<Normal> {
+        math::winding(self)
+            .and_then(Vec3::normalized)
+            .map(Vec3::to_normal)
+    }
+
+    /// Swap two vertices and negate the normal, turning the facet over
+    pub fn reverse_winding(&mut self) {
+        std::mem::swap(&mut self.v1, &mut self.v2);
+
+        self.normal = Normal {
+            i: -self.normal.i,
+            j: -self.normal.j,
+            k: -self.normal.k,
+        };
+    }
+
+    /// This triangle with its normal replaced by [`Triangle::computed_normal`],
+    /// or zero if there is none
+    pub(crate) fn with_computed_normal(self) -> Self {
+        Self {
+            normal: self.computed_normal().unwrap_or(Normal {
+                i: 0.0,
+                j: 0.0,
+                k: 0.0,
+            }),
+            ..self
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct Point {
     pub x: f32,
@@ -88,6 +125,53 @@ impl Hash for Point {
 
 impl Eq for Point {}
 
+/// A facet borrowed mutably from an [`StlFile`](crate::StlFile)
+///
+/// Changes made through this guard are written back to the file when it is
+/// dropped, keeping its normals and vertices consistent.
+#[derive(Debug)]
+pub struct TriangleMut<'a> {
+    triangle: Triangle,
+    normal: &'a mut Normal,
+    vertices: &'a mut [f32],
+}
+
+impl<'a> TriangleMut<'a> {
+    pub(crate) fn new(triangle: Triangle, normal: &'a mut Normal, vertices: &'a mut [f32]) -> Self {
+        debug_assert_eq!(vertices.len(), 9);
+
+        Self {
+            triangle,
+            normal,
+            vertices,
+        }
+    }
+}
+
+impl Deref for TriangleMut<'_> {
+    type Target = Triangle;
+
+    fn deref(&self) -> &Triangle {
+        &self.triangle
+    }
+}
+
+impl DerefMut for TriangleMut<'_> {
+    fn deref_mut(&mut self) -> &mut Triangle {
+        &mut self.triangle
+    }
+}
+
+impl Drop for TriangleMut<'_> {
+    fn drop(&mut self) {
+        let Triangle { normal, v0, v1, v2 } = self.triangle;
+
+        *self.normal = normal;
+        self.vertices
+            .copy_from_slice(&[v0.x, v0.y, v0.z, v1.x, v1.y, v1.z, v2.x, v2.y, v2.z]);
+    }
+}
+
 /// A vertex alongside the normal of the facet to which it belongs
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct VertexWithNormal {
@@ -128,9 +212,8 @@ impl<'a, I: Iterator<Item = Point>> Iterator for VertexWithNormalIterator<'a, I>
             normal: self.normals[self.normal_count],
         };
 
-        if self.vertex_count % 3 == 0 {
-            self.normal_count += 1;
-        }
+        // each normal is shared by three consecutive vertices
+        self.normal_count = self.vertex_count / 3;
 
         self.vertex_count += 1;
 
@@ -0,0 +1,155 @@
+use std::convert::TryInto;
+
+use crate::{
+    binary::{decode_facet, read_facet_count, FACET_LEN, HEADER_LEN},
+    StlFile, StlResult, Triangle,
+};
+
+/// A zero-copy view over a binary STL held in memory
+///
+/// Binary STL is a packed array of fixed-size records, so triangles can be
+/// decoded on demand without first copying the mesh into an [`StlFile`].
+///
+/// ```
+/// # use stl::{BinaryStlView, StlFile};
+/// let stl = StlFile::parse(b"solid foo
+/// facet normal 0 0 1 outer loop vertex 0 0 0 vertex 1 0 0 vertex 0 1 0 endloop endfacet
+/// endsolid foo").unwrap();
+///
+/// let mut buffer = Vec::new();
+/// stl.write_binary(&mut buffer).unwrap();
+///
+/// let view = BinaryStlView::new(&buffer).unwrap();
+/// assert_eq!(view.facet_count(), 1);
+/// assert_eq!(view.triangle(0), stl.triangles().next());
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct BinaryStlView<'a> {
+    buffer: &'a [u8],
+    num_of_facets: u32,
+}
+
+impl<'a> BinaryStlView<'a> {
+    /// Validate the header of a binary STL and check that the buffer is long
+    /// enough to hold every facet it declares
+    ///
+    /// Bytes following the last facet are ignored.
+    pub fn new(buffer: &'a [u8]) -> StlResult<Self> {
+        let num_of_facets = read_facet_count(buffer)?;
+
+        Ok(Self {
+            buffer,
+            num_of_facets,
+        })
+    }
+
+    pub fn header(&self) -> &'a [u8; 80] {
+        // `new` guarantees the buffer is at least `HEADER_LEN` long
+        self.buffer[..80].try_into().unwrap()
+    }
+
+    pub fn facet_count(&self) -> u32 {
+        self.num_of_facets
+    }
+
+    fn record(&self, facet: usize) -> Option<&'a [u8]> {
+        if facet >= self.num_of_facets as usize {
+            return None;
+        }
+
+        let start = HEADER_LEN + facet * FACET_LEN;
+
+        self.buffer.get(start..(start + FACET_LEN))
+    }
+
+    /// Decode a single facet, returning `None` if it is out of bounds
+    pub fn triangle(&self, facet: usize) -> Option<Triangle> {
+        self.record(facet).map(|record| decode_facet(record).0)
+    }
+
+    /// The attribute bytes of a single facet, returning `None` if it is out of bounds
+    pub fn attribute(&self, facet: usize) -> Option<u16> {
+        self.record(facet).map(|record| decode_facet(record).1)
+    }
+
+    fn records(&self) -> impl ExactSizeIterator<Item = &'a [u8]> + 'a {
+        self.buffer[HEADER_LEN..]
+            .chunks_exact(FACET_LEN)
+            .take(self.num_of_facets as usize)
+    }
+
+    pub fn triangles(&self) -> impl ExactSizeIterator<Item = Triangle> + 'a {
+        self.records().map(|record| decode_facet(record).0)
+    }
+
+    pub fn attributes(&self) -> impl ExactSizeIterator<Item = u16> + 'a {
+        self.records().map(|record| decode_facet(record).1)
+    }
+
+    /// Copy every facet into an owned [`StlFile`]
+    pub fn to_stl_file(&self) -> StlFile {
+        let mut file = StlFile::new();
+        file.set_header(*self.header());
+
+        for (triangle, attribute) in self.records().map(decode_facet) {
+            file.push_triangle_with_attribute(triangle, attribute);
+        }
+
+        file
+    }
+}
+
+/// A binary STL file mapped into memory
+///
+/// Opening a file this way is nearly instant regardless of its size, as pages
+/// are only read from disk when the corresponding triangles are accessed.
+///
+/// ```no_run
+/// # use stl::MmapStl;
+/// // SAFETY: nothing else modifies foo.stl while it is mapped
+/// let file = unsafe { MmapStl::from_path("./foo.stl") }.unwrap();
+/// let view = file.view();
+///
+/// println!("{} facets", view.facet_count());
+/// ```
+#[cfg(feature = "mmap")]
+#[derive(Debug)]
+pub struct MmapStl {
+    mmap: memmap2::Mmap,
+}
+
+#[cfg(feature = "mmap")]
+impl MmapStl {
+    /// Map a binary STL file into memory, validating its header
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated, by this or any other
+    /// process, while the returned `MmapStl` or any view of it is alive.
+    /// Doing so is undefined behavior, as the mapped bytes are read as an
+    /// immutable slice.
+    pub unsafe fn from_path(path: impl AsRef<std::path::Path>) -> StlResult<Self> {
+        let file = std::fs::File::open(path)?;
+
+        // SAFETY: the caller upholds that the file is not modified while
+        // mapped, as required by this function's contract
+        let mmap = memmap2::Mmap::map(&file)?;
+
+        read_facet_count(&mmap)?;
+
+        Ok(Self { mmap })
+    }
+
+    pub fn view(&self) -> BinaryStlView<'_> {
+        // the header was validated in `from_path`
+        BinaryStlView {
+            buffer: &self.mmap,
+            num_of_facets: u32::from_le_bytes([
+                self.mmap[80],
+                self.mmap[81],
+                self.mmap[82],
+                self.mmap[83],
+            ]),
+        }
+    }
+}
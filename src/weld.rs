@@ -0,0 +1,135 @@
+use std::collections::{hash_map::Entry, HashMap};
+
+use crate::{math::Vec3, Point};
+
+/// A cube of the spatial hash, with sides as long as the welding tolerance
+type Cell = (i64, i64, i64);
+
+/// Points welded together wherever they lie within a distance tolerance
+///
+/// Unlike the equality of [`Point`], which rounds to a fixed grid, points are
+/// compared by their actual distance. Each point is matched against the
+/// points already kept in its own and the 26 neighboring cells of a spatial
+/// hash, so points either side of a cell boundary still match. The first
+/// point of each cluster is kept as its representative, and later points
+/// weld to the nearest representative in range, so clusters do not chain
+/// together beyond `epsilon`.
+#[derive(Debug)]
+pub(crate) struct Welded {
+    /// The representative of each welded vertex
+    pub points: Vec<Point>,
+
+    /// The index into `points` of each input point
+    pub indices: Vec<u32>,
+}
+
+impl Welded {
+    /// Weld `points` which lie within `epsilon` of each other
+    ///
+    /// With an `epsilon` of zero or less, only points with identical
+    /// coordinates are welded. Points with NaN or infinite coordinates are
+    /// never welded.
+    pub fn new(points: impl Iterator<Item = Point>, epsilon: f32) -> Self {
+        if epsilon > 0.0 {
+            Self::within(points, epsilon)
+        } else {
+            Self::exact(points)
+        }
+    }
+
+    fn within(points: impl Iterator<Item = Point>, epsilon: f32) -> Self {
+        let epsilon = f64::from(epsilon);
+        let cell = |p: Vec3| {
+            (
+                (p.x / epsilon).floor() as i64,
+                (p.y / epsilon).floor() as i64,
+                (p.z / epsilon).floor() as i64,
+            )
+        };
+
+        let mut cells: HashMap<Cell, Vec<u32>> = HashMap::new();
+        let mut welded = Self {
+            points: Vec::new(),
+            indices: Vec::new(),
+        };
+
+        for point in points {
+            let p = Vec3::from(point);
+            let (x, y, z) = cell(p);
+
+            let mut nearest: Option<(f64, u32)> = None;
+
+            for dx in -1..=1 {
+                for dy in -1..=1 {
+                    for dz in -1..=1 {
+                        let key = (x.wrapping_add(dx), y.wrapping_add(dy), z.wrapping_add(dz));
+
+                        for &idx in cells.get(&key).into_iter().flatten() {
+                            let distance = (Vec3::from(welded.points[idx as usize]) - p).length();
+
+                            if distance <= epsilon
+                                && !matches!(nearest, Some((d, _)) if d <= distance)
+                            {
+                                nearest = Some((distance, idx));
+                            }
+                        }
+                    }
+                }
+            }
+
+            let idx = match nearest {
+                Some((_, idx)) => idx,
+                None => {
+                    let idx = welded.points.len() as u32;
+                    welded.points.push(point);
+
+                    if p.is_finite() {
+                        cells.entry((x, y, z)).or_default().push(idx);
+                    }
+
+                    idx
+                }
+            };
+
+            welded.indices.push(idx);
+        }
+
+        welded
+    }
+
+    fn exact(points: impl Iterator<Item = Point>) -> Self {
+        let mut ids: HashMap<[u32; 3], u32> = HashMap::new();
+        let mut welded = Self {
+            points: Vec::new(),
+            indices: Vec::new(),
+        };
+
+        for point in points {
+            let idx = welded.points.len() as u32;
+
+            if !Vec3::from(point).is_finite() {
+                welded.points.push(point);
+                welded.indices.push(idx);
+                continue;
+            }
+
+            // adding zero turns negative zero into positive zero
+            let key = [
+                (point.x + 0.0).to_bits(),
+                (point.y + 0.0).to_bits(),
+                (point.z + 0.0).to_bits(),
+            ];
+
+            match ids.entry(key) {
+                Entry::Occupied(val) => welded.indices.push(*val.get()),
+                Entry::Vacant(ptr) => {
+                    ptr.insert(idx);
+                    welded.points.push(point);
+                    welded.indices.push(idx);
+                }
+            }
+        }
+
+        welded
+    }
+}
@@ -0,0 +1,361 @@
+use std::io::{self, Seek, SeekFrom, Write};
+
+use crate::{StlFormat, StlResult, Triangle};
+
+/// How floating point numbers are written in ASCII output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FloatFormat {
+    /// The shortest representation which parses back to the same value
+    Shortest,
+
+    /// A fixed number of digits after the decimal point, e.g. `1.500000`
+    Fixed(usize),
+
+    /// Scientific notation with a fixed number of digits after the decimal
+    /// point, e.g. `1.500000e0`
+    Scientific(usize),
+}
+
+/// Formatting options for ASCII output
+///
+/// ```
+/// # use stl::{AsciiOptions, FloatFormat, StlFile};
+/// let stl = StlFile::parse(b"solid foo
+/// facet normal 0 0 1 outer loop vertex 0 0 0 vertex 1 0 0 vertex 0 1 0 endloop endfacet
+/// endsolid foo").unwrap();
+///
+/// let options = AsciiOptions {
+///     float_format: FloatFormat::Fixed(2),
+///     indent: "\t".to_owned(),
+/// };
+///
+/// let mut buffer = Vec::new();
+/// stl.write_ascii_with_options(&mut buffer, "foo", &options).unwrap();
+///
+/// assert!(buffer.starts_with(b"solid foo\n\tfacet normal 0.00 0.00 1.00\n"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AsciiOptions {
+    pub float_format: FloatFormat,
+
+    /// Indentation added for each level of nesting
+    pub indent: String,
+}
+
+impl Default for AsciiOptions {
+    fn default() -> Self {
+        Self {
+            float_format: FloatFormat::Shortest,
+            indent: "  ".to_owned(),
+        }
+    }
+}
+
+/// Rewrites the facet count of a binary header which begins at the given position
+type PatchFacetCount<W> = fn(&mut W, u64, u32) -> io::Result<()>;
+
+/// Writes triangles one at a time without buffering the whole mesh
+///
+/// Binary output requires the number of facets up front. When the writer
+/// implements [`Seek`], [`StlWriter::binary`] writes a placeholder count and
+/// patches it in [`StlWriter::finish`]. Otherwise, the count must be declared
+/// with [`StlWriter::binary_with_count`].
+///
+/// Output is written directly to `W`, so wrapping files in a
+/// [`BufWriter`](std::io::BufWriter) is recommended.
+///
+/// ```
+/// # use std::io::Cursor;
+/// # use stl::{Normal, Point, StlFile, StlWriter, Triangle};
+/// let triangle = Triangle {
+///     normal: Normal { i: 0.0, j: 0.0, k: 1.0 },
+///     v0: Point { x: 0.0, y: 0.0, z: 0.0 },
+///     v1: Point { x: 1.0, y: 0.0, z: 0.0 },
+///     v2: Point { x: 0.0, y: 1.0, z: 0.0 },
+/// };
+///
+/// let mut writer = StlWriter::binary(Cursor::new(Vec::new())).unwrap();
+/// writer.write_triangle(triangle).unwrap();
+/// writer.write_triangle(triangle).unwrap();
+/// let buffer = writer.finish().unwrap().into_inner();
+///
+/// assert_eq!(StlFile::parse(&buffer).unwrap().facet_count(), 2);
+/// ```
+pub struct StlWriter<W: Write> {
+    writer: W,
+    format: StlFormat,
+    name: String,
+    ascii_options: AsciiOptions,
+    facet_count: u32,
+    declared_facet_count: Option<u32>,
+    patch: Option<(PatchFacetCount<W>, u64)>,
+}
+
+impl<W: Write> std::fmt::Debug for StlWriter<W> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StlWriter")
+            .field("format", &self.format)
+            .field("name", &self.name)
+            .field("facet_count", &self.facet_count)
+            .field("declared_facet_count", &self.declared_facet_count)
+            .finish()
+    }
+}
+
+impl<W: Write + Seek> StlWriter<W> {
+    /// Begin a binary STL whose facet count is filled in by [`StlWriter::finish`]
+    pub fn binary(mut writer: W) -> StlResult<Self> {
+        let start = writer.stream_position()?;
+
+        write_binary_header(&mut writer, &[0; 80], 0)?;
+
+        Ok(Self {
+            writer,
+            format: StlFormat::Binary,
+            name: String::new(),
+            ascii_options: AsciiOptions::default(),
+            facet_count: 0,
+            declared_facet_count: None,
+            patch: Some((patch_facet_count::<W>, start)),
+        })
+    }
+}
+
+impl<W: Write> StlWriter<W> {
+    /// Begin a binary STL that will contain exactly `num_of_facets` facets
+    pub fn binary_with_count(writer: W, num_of_facets: u32) -> StlResult<Self> {
+        Self::binary_with_header(writer, [0; 80], num_of_facets)
+    }
+
+    /// Begin a binary STL with a custom 80-byte header that will contain
+    /// exactly `num_of_facets` facets
+    pub fn binary_with_header(
+        mut writer: W,
+        header: [u8; 80],
+        num_of_facets: u32,
+    ) -> StlResult<Self> {
+        write_binary_header(&mut writer, &header, num_of_facets)?;
+
+        Ok(Self {
+            writer,
+            format: StlFormat::Binary,
+            name: String::new(),
+            ascii_options: AsciiOptions::default(),
+            facet_count: 0,
+            declared_facet_count: Some(num_of_facets),
+            patch: None,
+        })
+    }
+
+    /// Begin an ASCII STL containing a single solid called `name`
+    ///
+    /// A name is a single token in ASCII STL, so any whitespace within it is
+    /// written as `_`. An empty name is written as nothing after `solid`.
+    pub fn ascii(writer: W, name: &str) -> StlResult<Self> {
+        Self::ascii_with_options(writer, name, AsciiOptions::default())
+    }
+
+    /// Begin an ASCII STL, formatted according to `options`
+    pub fn ascii_with_options(mut writer: W, name: &str, options: AsciiOptions) -> StlResult<Self> {
+        let name = solid_name(name);
+        writeln!(writer, "solid {}", name)?;
+
+        Ok(Self {
+            writer,
+            format: StlFormat::Ascii,
+            name,
+            ascii_options: options,
+            facet_count: 0,
+            declared_facet_count: None,
+            patch: None,
+        })
+    }
+
+    /// End the current solid and begin another called `name`, which is
+    /// written as in [`StlWriter::ascii`]
+    ///
+    /// Only ASCII STL supports multiple solids. For binary output this is an error.
+    pub fn next_solid(&mut self, name: &str) -> StlResult<()> {
+        if self.format != StlFormat::Ascii {
+            return Err(invalid_input("binary STL cannot contain multiple solids").into());
+        }
+
+        writeln!(self.writer, "endsolid {}", self.name)?;
+
+        self.name = solid_name(name);
+        writeln!(self.writer, "solid {}", self.name)?;
+
+        Ok(())
+    }
+
+    pub fn format(&self) -> StlFormat {
+        self.format
+    }
+
+    /// Number of facets written so far
+    pub fn facet_count(&self) -> u32 {
+        self.facet_count
+    }
+
+    pub fn write_triangle(&mut self, triangle: Triangle) -> StlResult<()> {
+        self.write_triangle_with_attribute(triangle, 0)
+    }
+
+    /// Write a triangle followed by the given attribute bytes
+    ///
+    /// Attributes cannot be represented in ASCII STL and are ignored there.
+    pub fn write_triangle_with_attribute(
+        &mut self,
+        triangle: Triangle,
+        attribute: u16,
+    ) -> StlResult<()> {
+        let limit = self.declared_facet_count.unwrap_or(u32::MAX);
+
+        if self.facet_count == limit {
+            return Err(invalid_input("more facets written than declared in the header").into());
+        }
+
+        match self.format {
+            StlFormat::Ascii => {
+                write_ascii_triangle(&mut self.writer, triangle, &self.ascii_options)?
+            }
+            StlFormat::Binary => write_binary_triangle(&mut self.writer, triangle, attribute)?,
+        }
+
+        self.facet_count += 1;
+
+        Ok(())
+    }
+
+    /// Complete the file, returning the underlying writer
+    ///
+    /// Dropping the writer without calling this method may leave an invalid
+    /// file behind.
+    pub fn finish(mut self) -> StlResult<W> {
+        match self.format {
+            StlFormat::Ascii => writeln!(self.writer, "endsolid {}", self.name)?,
+            StlFormat::Binary => {
+                if let Some((patch, start)) = self.patch {
+                    patch(&mut self.writer, start, self.facet_count)?;
+                }
+
+                if let Some(declared) = self.declared_facet_count {
+                    if declared != self.facet_count {
+                        return Err(invalid_input(
+                            "fewer facets written than declared in the header",
+                        )
+                        .into());
+                    }
+                }
+            }
+        }
+
+        self.writer.flush()?;
+
+        Ok(self.writer)
+    }
+}
+
+fn invalid_input(msg: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, msg)
+}
+
+fn patch_facet_count<W: Write + Seek>(
+    writer: &mut W,
+    start: u64,
+    num_of_facets: u32,
+) -> io::Result<()> {
+    let end = writer.stream_position()?;
+
+    writer.seek(SeekFrom::Start(start + 80))?;
+    writer.write_all(&num_of_facets.to_le_bytes())?;
+    writer.seek(SeekFrom::Start(end))?;
+
+    Ok(())
+}
+
+fn write_binary_header(
+    writer: &mut impl Write,
+    header: &[u8; 80],
+    num_of_facets: u32,
+) -> io::Result<()> {
+    writer.write_all(header)?;
+    writer.write_all(&num_of_facets.to_le_bytes())
+}
+
+fn write_binary_triangle(
+    writer: &mut impl Write,
+    triangle: Triangle,
+    attribute: u16,
+) -> io::Result<()> {
+    fn write_vec3(x: f32, y: f32, z: f32, writer: &mut impl Write) -> io::Result<()> {
+        writer.write_all(&x.to_le_bytes())?;
+        writer.write_all(&y.to_le_bytes())?;
+        writer.write_all(&z.to_le_bytes())?;
+
+        Ok(())
+    }
+
+    write_vec3(
+        triangle.normal.i,
+        triangle.normal.j,
+        triangle.normal.k,
+        writer,
+    )?;
+    write_vec3(triangle.v0.x, triangle.v0.y, triangle.v0.z, writer)?;
+    write_vec3(triangle.v1.x, triangle.v1.y, triangle.v1.z, writer)?;
+    write_vec3(triangle.v2.x, triangle.v2.y, triangle.v2.z, writer)?;
+
+    writer.write_all(&attribute.to_le_bytes())
+}
+
+fn write_float(writer: &mut impl Write, float: f32, format: FloatFormat) -> io::Result<()> {
+    match format {
+        FloatFormat::Shortest => write!(writer, "{}", float),
+        FloatFormat::Fixed(precision) => write!(writer, "{:.*}", precision, float),
+        FloatFormat::Scientific(precision) => write!(writer, "{:.*e}", precision, float),
+    }
+}
+
+fn write_ascii_vec3(
+    writer: &mut impl Write,
+    x: f32,
+    y: f32,
+    z: f32,
+    format: FloatFormat,
+) -> io::Result<()> {
+    write_float(writer, x, format)?;
+    writer.write_all(b" ")?;
+    write_float(writer, y, format)?;
+    writer.write_all(b" ")?;
+    write_float(writer, z, format)?;
+    writer.write_all(b"\n")
+}
+
+fn write_ascii_triangle(
+    writer: &mut impl Write,
+    triangle: Triangle,
+    options: &AsciiOptions,
+) -> io::Result<()> {
+    let Triangle { normal, v0, v1, v2 } = triangle;
+    let indent = &options.indent;
+
+    write!(writer, "{}facet normal ", indent)?;
+    write_ascii_vec3(writer, normal.i, normal.j, normal.k, options.float_format)?;
+    writeln!(writer, "{0}{0}outer loop", indent)?;
+
+    for v in &[v0, v1, v2] {
+        write!(writer, "{0}{0}{0}vertex ", indent)?;
+        write_ascii_vec3(writer, v.x, v.y, v.z, options.float_format)?;
+    }
+
+    writeln!(writer, "{0}{0}endloop", indent)?;
+    writeln!(writer, "{}endfacet", indent)
+}
+
+/// `name` as a single token, with each whitespace character replaced by `_`
+fn solid_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_whitespace() { '_' } else { c })
+        .collect()
+}
@@ -0,0 +1,114 @@
+mod common;
+
+use common::{point, triangle};
+use stl::{CleanupSummary, StlFile, Triangle};
+
+fn facet(v0: [f32; 3], v1: [f32; 3], v2: [f32; 3]) -> Triangle {
+    triangle(point(v0), point(v1), point(v2))
+}
+
+fn stl(triangles: Vec<Triangle>) -> StlFile {
+    triangles.into_iter().collect()
+}
+
+#[test]
+fn removes_degenerate_facets() {
+    let mut stl = stl(vec![
+        facet([0., 0., 0.], [1., 0., 0.], [0., 1., 0.]),
+        facet([0., 0., 0.], [0., 0., 0.], [0., 1., 0.]),
+        facet([0., 0., 0.], [1., 0., 0.], [2., 0., 0.]),
+        facet([0., 0., 0.], [1., 0., 0.], [0., 1e-6, 0.]),
+        facet([5., 5., 5.], [6., 5., 5.], [5., 6., 5.]),
+        facet([1., 1., 1.], [1., 2., 1.], [1., 1. + 1e-7, 1.]),
+    ]);
+
+    let summary = stl.remove_degenerate(1e-3);
+    assert_eq!(
+        summary,
+        CleanupSummary {
+            repeated_vertices: 2,
+            zero_area: 2,
+            duplicates: 0,
+            reversed_duplicates: 0,
+        }
+    );
+    assert_eq!(summary.total(), 4);
+
+    assert_eq!(stl.facet_count(), 2);
+    assert_eq!(stl.triangle(1).unwrap().v0, point([5., 5., 5.]));
+}
+
+#[test]
+fn zero_threshold_keeps_slivers() {
+    let mut stl = stl(vec![
+        facet([0., 0., 0.], [1., 0., 0.], [0., 1e-6, 0.]),
+        facet([0., 0., 0.], [1., 0., 0.], [2., 0., 0.]),
+    ]);
+
+    assert_eq!(stl.remove_degenerate(0.0).zero_area, 1);
+    assert_eq!(stl.facet_count(), 1);
+}
+
+#[test]
+fn keeps_non_finite_facets() {
+    let mut stl = stl(vec![
+        facet([f32::NAN, 0., 0.], [1., 0., 0.], [0., 1., 0.]),
+        facet([f32::NAN, 0., 0.], [0., 0., 0.], [0., 1., 0.]),
+        facet([f32::INFINITY, 0., 0.], [0., 0., 0.], [0., 1., 0.]),
+    ]);
+
+    assert_eq!(stl.remove_degenerate(1.0).total(), 0);
+    assert_eq!(stl.facet_count(), 3);
+}
+
+#[test]
+fn removes_duplicate_facets() {
+    let mut stl = stl(vec![
+        facet([0., 0., 0.], [1., 0., 0.], [0., 1., 0.]),
+        facet([1., 0., 0.], [0., 1., 0.], [0., 0., 0.]),
+        facet([0., 0., 0.], [0., 1., 0.], [1., 0., 0.]),
+        facet([0., 0., 0.], [1., 0., 0.], [0., 0., 1.]),
+        facet([0., 0., 0.], [1., 0., 0.], [0., 1., 0.]),
+        facet([0., 1., 0.], [1., 0., 0.], [0., 0., 0.]),
+    ]);
+    stl.attributes_mut()[0] = 7;
+
+    let summary = stl.remove_duplicate_facets();
+    assert_eq!(
+        summary,
+        CleanupSummary {
+            repeated_vertices: 0,
+            zero_area: 0,
+            duplicates: 2,
+            reversed_duplicates: 2,
+        }
+    );
+
+    assert_eq!(stl.facet_count(), 2);
+    assert_eq!(stl.attributes(), [7, 0]);
+    assert_eq!(stl.triangle(1).unwrap().v2, point([0., 0., 1.]));
+
+    assert_eq!(stl.remove_duplicate_facets().total(), 0);
+}
+
+#[test]
+fn back_to_back_facets_keep_first() {
+    let mut stl = stl(vec![
+        facet([0., 0., 0.], [0., 1., 0.], [1., 0., 0.]),
+        facet([0., 0., 0.], [1., 0., 0.], [0., 1., 0.]),
+    ]);
+
+    assert_eq!(stl.remove_duplicate_facets().reversed_duplicates, 1);
+    assert_eq!(
+        stl.triangle(0).unwrap(),
+        facet([0., 0., 0.], [0., 1., 0.], [1., 0., 0.])
+    );
+}
+
+#[test]
+fn empty_file() {
+    let mut stl = StlFile::new();
+
+    assert_eq!(stl.remove_degenerate(1.0), CleanupSummary::default());
+    assert_eq!(stl.remove_duplicate_facets(), CleanupSummary::default());
+}
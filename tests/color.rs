@@ -0,0 +1,84 @@
+mod common;
+
+use common::ASCII;
+use stl::{Color, ColorFormat, Material, StlFile};
+
+fn round_trip(stl: &StlFile) -> StlFile {
+    let mut buffer = Vec::new();
+    stl.write_binary(&mut buffer).unwrap();
+    StlFile::parse(&buffer).unwrap()
+}
+
+#[test]
+fn facet_colors_survive_round_trip() {
+    let mut stl = StlFile::parse(ASCII).unwrap();
+    assert_eq!(stl.attributes(), &[0, 0]);
+
+    let teal = Color {
+        r: 0,
+        g: 132,
+        b: 132,
+    };
+
+    for format in &[ColorFormat::VisCam, ColorFormat::Materialise] {
+        stl.set_facet_color(0, *format, Some(teal));
+        stl.set_facet_color(1, *format, None);
+
+        let parsed = round_trip(&stl);
+        assert_eq!(parsed.attributes(), stl.attributes());
+        assert_eq!(parsed.facet_color(0, *format), Some(teal));
+        assert_eq!(parsed.facet_color(1, *format), None);
+        assert_eq!(parsed.facet_color(2, *format), None);
+    }
+}
+
+#[test]
+fn attributes_survive_split_islands() {
+    let mut stl = StlFile::parse(ASCII).unwrap();
+    stl.attributes_mut().copy_from_slice(&[0x1234, 0x1234]);
+
+    let islands = stl.split_islands();
+    assert_eq!(islands.len(), 1);
+    assert_eq!(islands[0].attributes(), &[0x1234, 0x1234]);
+}
+
+#[test]
+fn header_color_and_material() {
+    let mut stl = StlFile::parse(ASCII).unwrap();
+    assert_eq!(stl.header_color(), None);
+    assert_eq!(stl.header_material(), None);
+
+    let mut header = [b' '; 80];
+    header[..10].copy_from_slice(b"Magics STL");
+    stl.set_header(header);
+
+    let material = Material {
+        diffuse: [255, 0, 0, 255],
+        specular: [255, 255, 255, 255],
+        ambient: [32, 32, 32, 255],
+    };
+
+    stl.set_header_color([10, 20, 30, 255]);
+    stl.set_header_material(material);
+
+    let parsed = round_trip(&stl);
+    assert!(parsed.header().starts_with(b"Magics STL"));
+    assert_eq!(parsed.header_color(), Some([10, 20, 30, 255]));
+    assert_eq!(parsed.header_material(), Some(material));
+
+    // replacing an existing value leaves the rest of the header alone
+    stl.set_header_color([1, 2, 3, 4]);
+    assert_eq!(stl.header_color(), Some([1, 2, 3, 4]));
+    assert_eq!(stl.header_material(), Some(material));
+}
+
+#[test]
+fn existing_header_keys_are_found() {
+    let mut header = [0; 80];
+    header[..10].copy_from_slice(b"COLOR=\x01\x02\x03\x04");
+
+    let mut stl = StlFile::parse(ASCII).unwrap();
+    stl.set_header(header);
+
+    assert_eq!(stl.header_color(), Some([1, 2, 3, 4]));
+}
@@ -0,0 +1,91 @@
+//! Fixtures shared by the integration tests
+//!
+//! Each test binary compiles its own copy of this module and uses only part
+//! of it.
+#![allow(dead_code)]
+
+use stl::{Normal, Point, StlFile, Triangle};
+
+/// Two facets covering the bottom face of a unit cube
+pub const ASCII: &[u8] = b"solid cube
+  facet normal 0 0 -1
+    outer loop
+      vertex 0 0 0
+      vertex 1 1 0
+      vertex 1 0 0
+    endloop
+  endfacet
+  facet normal 0 0 -1
+    outer loop
+      vertex 0 0 0
+      vertex 0 1 0
+      vertex 1 1 0
+    endloop
+  endfacet
+endsolid cube
+";
+
+/// [`ASCII`] converted to binary
+pub fn binary() -> Vec<u8> {
+    to_binary(ASCII)
+}
+
+/// Parse `input` and write it back out in binary
+pub fn to_binary(input: &[u8]) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    StlFile::parse(input)
+        .unwrap()
+        .write_binary(&mut buffer)
+        .unwrap();
+    buffer
+}
+
+/// The faces of a unit cube, each wound counter-clockwise when seen from outside
+pub const FACES: [[[f32; 3]; 4]; 6] = [
+    [[0., 0., 0.], [0., 1., 0.], [1., 1., 0.], [1., 0., 0.]],
+    [[0., 0., 1.], [1., 0., 1.], [1., 1., 1.], [0., 1., 1.]],
+    [[0., 0., 0.], [1., 0., 0.], [1., 0., 1.], [0., 0., 1.]],
+    [[0., 1., 0.], [0., 1., 1.], [1., 1., 1.], [1., 1., 0.]],
+    [[0., 0., 0.], [0., 0., 1.], [0., 1., 1.], [0., 1., 0.]],
+    [[1., 0., 0.], [1., 1., 0.], [1., 1., 1.], [1., 0., 1.]],
+];
+
+pub fn point([x, y, z]: [f32; 3]) -> Point {
+    Point { x, y, z }
+}
+
+/// A facet with a normal of zero
+pub fn triangle(v0: Point, v1: Point, v2: Point) -> Triangle {
+    Triangle {
+        normal: Normal {
+            i: 0.0,
+            j: 0.0,
+            k: 0.0,
+        },
+        v0,
+        v1,
+        v2,
+    }
+}
+
+/// Two facets covering a quad, following its winding
+pub fn quad([a, b, c, d]: [Point; 4]) -> Vec<Triangle> {
+    vec![triangle(a, b, c), triangle(a, c, d)]
+}
+
+/// A cube with sides of length `size` whose lowest corner is at `origin`,
+/// with two facets for each of [`FACES`] in turn
+pub fn cube(size: f32, origin: [f32; 3]) -> Vec<Triangle> {
+    FACES
+        .iter()
+        .flat_map(|face| {
+            quad(face.map(|[x, y, z]| {
+                point([
+                    origin[0] + x * size,
+                    origin[1] + y * size,
+                    origin[2] + z * size,
+                ])
+            }))
+        })
+        .collect()
+}
@@ -0,0 +1,221 @@
+mod common;
+
+use common::{binary, ASCII};
+use stl::{LimitExceeded, ParseOptions, StlError, StlFile};
+
+fn limited(max_input_len: usize) -> ParseOptions {
+    ParseOptions {
+        max_input_len: Some(max_input_len),
+        ..ParseOptions::default()
+    }
+}
+
+#[test]
+fn from_reader() {
+    assert_eq!(
+        StlFile::from_reader(ASCII).unwrap(),
+        StlFile::parse(ASCII).unwrap()
+    );
+    assert_eq!(
+        StlFile::from_reader(binary().as_slice()).unwrap(),
+        StlFile::parse(&binary()).unwrap()
+    );
+}
+
+#[test]
+fn from_reader_with_limit() {
+    let (stl, warnings) = StlFile::from_reader_with_options(ASCII, &limited(ASCII.len())).unwrap();
+    assert_eq!(stl, StlFile::parse(ASCII).unwrap());
+    assert!(warnings.is_empty());
+
+    match StlFile::from_reader_with_options(ASCII, &limited(ASCII.len() - 1)) {
+        Err(StlError::LimitExceeded(LimitExceeded::InputLen { .. })) => {}
+        result => panic!("expected exceeded input length, got {:?}", result),
+    }
+}
+
+#[cfg(feature = "compression")]
+mod compressed {
+    use std::io::{Cursor, Write};
+
+    use flate2::{write::GzEncoder, Compression};
+    use stl::{LimitExceeded, ParseOptions, StlError, StlFile};
+    use zip::{write::FileOptions, CompressionMethod, ZipWriter};
+
+    use super::{binary, limited, ASCII};
+
+    fn gzip(input: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(input).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn zip(entries: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+
+        for (name, contents) in entries {
+            writer.start_file(*name, options).unwrap();
+            writer.write_all(contents).unwrap();
+        }
+
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn gzip_input() {
+        for input in &[ASCII, &binary()] {
+            let expected = StlFile::parse(input).unwrap();
+
+            assert_eq!(
+                StlFile::from_reader(gzip(input).as_slice()).unwrap(),
+                expected
+            );
+
+            let entries = StlFile::entries_from_reader(gzip(input).as_slice()).unwrap();
+            assert_eq!(entries, vec![(String::new(), expected)]);
+        }
+    }
+
+    #[test]
+    fn zip_entries() {
+        let binary = binary();
+        let archive = zip(&[
+            ("parts/a.stl", ASCII),
+            ("readme.txt", b"not an stl"),
+            ("parts/B.STL", &binary),
+        ]);
+
+        let entries = StlFile::entries_from_reader(archive.as_slice()).unwrap();
+
+        assert_eq!(
+            entries,
+            vec![
+                ("parts/a.stl".to_owned(), StlFile::parse(ASCII).unwrap()),
+                ("parts/B.STL".to_owned(), StlFile::parse(&binary).unwrap()),
+            ]
+        );
+
+        let merged = StlFile::from_reader(archive.as_slice()).unwrap();
+        assert_eq!(merged.facet_count(), 4);
+        assert_eq!(merged.name(), "cube");
+    }
+
+    #[test]
+    fn zip_without_stl_is_an_error() {
+        let archive = zip(&[("readme.txt", b"not an stl")]);
+
+        assert!(StlFile::from_reader(archive.as_slice()).is_err());
+        assert!(StlFile::entries_from_reader(archive.as_slice()).is_err());
+    }
+
+    #[test]
+    fn entries_from_path() {
+        let path = std::env::temp_dir().join(format!("stl-{}-cube.stl.gz", std::process::id()));
+        std::fs::write(&path, gzip(ASCII)).unwrap();
+
+        let entries = StlFile::entries_from_path(&path);
+        let merged = StlFile::from_path(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        let name = format!("stl-{}-cube.stl", std::process::id());
+        assert_eq!(
+            entries.unwrap(),
+            vec![(name, StlFile::parse(ASCII).unwrap())]
+        );
+        assert_eq!(merged.unwrap(), StlFile::parse(ASCII).unwrap());
+    }
+
+    #[test]
+    fn decompression_bombs_are_limited() {
+        // a megabyte of zeros compresses to about a kilobyte
+        let bomb = vec![0; 1024 * 1024];
+        let options = limited(64 * 1024);
+
+        let gzipped = gzip(&bomb);
+        assert!(gzipped.len() < 64 * 1024);
+
+        let zipped = zip(&[("a.stl", ASCII), ("bomb.stl", &bomb)]);
+        assert!(zipped.len() < 64 * 1024);
+
+        for input in &[gzipped, zipped] {
+            match StlFile::from_reader_with_options(input.as_slice(), &options) {
+                Err(StlError::LimitExceeded(LimitExceeded::InputLen { limit })) => {
+                    assert_eq!(limit, 64 * 1024)
+                }
+                result => panic!("expected exceeded input length, got {:?}", result),
+            }
+
+            assert!(matches!(
+                StlFile::entries_from_reader_with_options(input.as_slice(), &options),
+                Err(StlError::LimitExceeded(LimitExceeded::InputLen { .. }))
+            ));
+        }
+    }
+
+    #[test]
+    fn limits_apply_to_whole_archive() {
+        // each entry is within the limits alone, but not all of them together
+        let mut entry = vec![0; 84 + 50 * 2000];
+        entry[80..84].copy_from_slice(&2000u32.to_le_bytes());
+
+        let names = (0..16)
+            .map(|idx| format!("{}.stl", idx))
+            .collect::<Vec<_>>();
+        let archive = zip(&names
+            .iter()
+            .map(|name| (name.as_str(), entry.as_slice()))
+            .collect::<Vec<_>>());
+
+        let options = limited(4 * entry.len());
+        assert!(archive.len() < entry.len());
+
+        match StlFile::from_reader_with_options(archive.as_slice(), &options) {
+            Err(StlError::LimitExceeded(LimitExceeded::InputLen { limit })) => {
+                assert_eq!(limit, 4 * entry.len())
+            }
+            result => panic!("expected exceeded input length, got {:?}", result),
+        }
+
+        let options = ParseOptions {
+            max_facets: Some(5000),
+            ..ParseOptions::default()
+        };
+
+        assert!(matches!(
+            StlFile::from_reader_with_options(archive.as_slice(), &options),
+            Err(StlError::LimitExceeded(LimitExceeded::FacetCount {
+                limit: 5000
+            }))
+        ));
+        assert!(matches!(
+            StlFile::entries_from_reader_with_options(archive.as_slice(), &options),
+            Err(StlError::LimitExceeded(LimitExceeded::FacetCount {
+                limit: 5000
+            }))
+        ));
+    }
+
+    #[test]
+    fn zip_entries_with_options() {
+        let archive = zip(&[("a.stl", ASCII), ("b.stl", ASCII)]);
+
+        // the limit applies to the archive as well as its entries together
+        let options = limited(archive.len().max(2 * ASCII.len()));
+        let entries =
+            StlFile::entries_from_reader_with_options(archive.as_slice(), &options).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert!(entries.iter().all(|(_, _, warnings)| warnings.is_empty()));
+
+        let options = ParseOptions {
+            max_facets: Some(1),
+            ..ParseOptions::default()
+        };
+        assert!(matches!(
+            StlFile::from_reader_with_options(archive.as_slice(), &options),
+            Err(StlError::LimitExceeded(LimitExceeded::FacetCount {
+                limit: 1
+            }))
+        ));
+    }
+}
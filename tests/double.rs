@@ -0,0 +1,91 @@
+use stl::{Point, Point64, StlFile, StlFile64};
+
+const ASCII: &[u8] = b"solid cube
+  facet normal 0 0 -1
+    outer loop
+      vertex 0.1 0.2 0.3
+      vertex 1 1 0
+      vertex 1 0 0
+    endloop
+  endfacet
+  facet normal 0 0 1
+    outer loop
+      vertex 0 0 1
+      vertex 1 0 1
+      vertex 1 1 1
+    endloop
+  endfacet
+endsolid cube
+";
+
+#[test]
+fn conversion_is_lossless() {
+    let mut buffer = Vec::new();
+    StlFile::parse(ASCII)
+        .unwrap()
+        .write_binary(&mut buffer)
+        .unwrap();
+    let stl = StlFile::parse(&buffer).unwrap();
+
+    let stl64 = stl.to_f64();
+    assert_eq!(stl64, StlFile64::from(stl.clone()));
+    assert_eq!(stl64.facet_count(), 2);
+    assert_eq!(stl64.attributes(), stl.attributes());
+    assert_eq!(stl64.header(), stl.header());
+
+    for (v64, v) in stl64.vertex_buffer().iter().zip(stl.vertex_buffer()) {
+        assert_eq!(*v64 as f32, *v);
+        assert_eq!(*v64, f64::from(*v));
+    }
+
+    let narrowed = stl64.to_f32();
+    assert_eq!(narrowed.vertex_buffer(), stl.vertex_buffer());
+    assert_eq!(
+        narrowed.triangles().collect::<Vec<_>>(),
+        stl.triangles().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn large_offsets_round_trip() {
+    let stl = StlFile::parse(ASCII).unwrap();
+    let offset = 1_000_000.0;
+
+    // in single precision, the offset swamps the fractional coordinates
+    let single = f64::from((0.1_f32 + offset as f32) - offset as f32);
+    assert_ne!(single as f32, 0.1);
+
+    let mut stl64 = stl.to_f64();
+    for v in stl64.vertex_buffer_mut() {
+        *v += offset;
+    }
+    for v in stl64.vertex_buffer_mut() {
+        *v -= offset;
+    }
+
+    assert_eq!(stl64.to_f32().vertex_buffer(), stl.vertex_buffer());
+}
+
+#[test]
+fn triangles() {
+    let stl = StlFile::parse(ASCII).unwrap();
+    let stl64 = stl.to_f64();
+
+    let triangles = stl64.triangles().collect::<Vec<_>>();
+    assert_eq!(triangles.len(), 2);
+    assert_eq!(triangles[0].normal.k, -1.0);
+    assert_eq!(
+        triangles[1].v2,
+        Point64::from(Point {
+            x: 1.0,
+            y: 1.0,
+            z: 1.0
+        })
+    );
+
+    for (t64, t) in triangles.into_iter().zip(stl.triangles()) {
+        assert_eq!(t64.to_f32(), t);
+    }
+
+    assert_eq!(stl64.vertices().count(), 6);
+}
@@ -0,0 +1,111 @@
+use stl::{Normal, Point, StlFile, Triangle};
+
+fn triangle(z: f32) -> Triangle {
+    Triangle {
+        normal: Normal {
+            i: 0.0,
+            j: 0.0,
+            k: 1.0,
+        },
+        v0: Point { x: 0.0, y: 0.0, z },
+        v1: Point { x: 1.0, y: 0.0, z },
+        v2: Point { x: 0.0, y: 1.0, z },
+    }
+}
+
+fn z_values(stl: &StlFile) -> Vec<f32> {
+    stl.triangles().map(|t| t.v0.z).collect()
+}
+
+fn assert_consistent(stl: &StlFile) {
+    let facets = stl.facet_count() as usize;
+
+    assert_eq!(stl.normals().len(), facets);
+    assert_eq!(stl.attributes().len(), facets);
+    assert_eq!(stl.vertex_buffer().len(), facets * 9);
+}
+
+#[test]
+fn construction() {
+    let empty = StlFile::new();
+    assert_eq!(empty, StlFile::default());
+    assert_eq!(empty.facet_count(), 0);
+
+    let stl = StlFile::from_triangles((0..3).map(|z| triangle(z as f32)));
+    assert_eq!(z_values(&stl), vec![0.0, 1.0, 2.0]);
+    assert_eq!(stl.attributes(), &[0, 0, 0]);
+    assert_consistent(&stl);
+
+    let collected = (0..3).map(|z| triangle(z as f32)).collect::<StlFile>();
+    assert_eq!(collected, stl);
+}
+
+#[test]
+fn push_and_extend() {
+    let mut stl = StlFile::new();
+
+    stl.push_triangle(triangle(0.0));
+    stl.push_triangle_with_attribute(triangle(1.0), 7);
+    stl.extend(vec![triangle(2.0), triangle(3.0)]);
+
+    assert_eq!(z_values(&stl), vec![0.0, 1.0, 2.0, 3.0]);
+    assert_eq!(stl.attributes(), &[0, 7, 0, 0]);
+    assert_eq!(stl.triangle(1), Some(triangle(1.0)));
+    assert_eq!(stl.triangle(4), None);
+    assert_eq!(stl.triangle(usize::MAX / 4), None);
+    assert_eq!(stl.triangle(usize::MAX), None);
+    assert_consistent(&stl);
+
+    // round trips through the existing writers
+    let mut buffer = Vec::new();
+    stl.write_binary(&mut buffer).unwrap();
+    assert_eq!(StlFile::parse(&buffer).unwrap(), stl);
+}
+
+#[test]
+fn remove_and_retain() {
+    let mut stl = StlFile::new();
+    for z in 0..6 {
+        stl.push_triangle_with_attribute(triangle(z as f32), z);
+    }
+
+    assert_eq!(stl.remove_triangle(1), triangle(1.0));
+    assert_eq!(z_values(&stl), vec![0.0, 2.0, 3.0, 4.0, 5.0]);
+    assert_eq!(stl.attributes(), &[0, 2, 3, 4, 5]);
+    assert_consistent(&stl);
+
+    stl.retain_triangles(|t| [0.0, 2.0, 4.0].contains(&t.v0.z));
+    assert_eq!(z_values(&stl), vec![0.0, 2.0, 4.0]);
+    assert_eq!(stl.attributes(), &[0, 2, 4]);
+    assert_consistent(&stl);
+
+    stl.retain_triangles(|_| false);
+    assert_eq!(stl, StlFile::new());
+}
+
+#[test]
+#[should_panic]
+fn remove_out_of_bounds() {
+    StlFile::from_triangles(vec![triangle(0.0)]).remove_triangle(1);
+}
+
+#[test]
+fn triangle_mut() {
+    let mut stl = StlFile::from_triangles(vec![triangle(0.0), triangle(1.0)]);
+
+    {
+        let mut t = stl.triangle_mut(1).unwrap();
+        t.v1.x = 5.0;
+        t.normal.k = -1.0;
+    }
+
+    let mut expected = triangle(1.0);
+    expected.v1.x = 5.0;
+    expected.normal.k = -1.0;
+
+    assert_eq!(stl.triangle(1), Some(expected));
+    assert_eq!(stl.triangle(0), Some(triangle(0.0)));
+    assert_eq!(stl.vertex_buffer()[9 + 3], 5.0);
+    assert!(stl.triangle_mut(2).is_none());
+    assert!(stl.triangle_mut(usize::MAX / 4).is_none());
+}
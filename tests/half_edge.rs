@@ -0,0 +1,97 @@
+mod common;
+
+use common::{point, triangle};
+use stl::{HalfEdgeMesh, StlFile};
+
+fn cube() -> StlFile {
+    StlFile::from_triangles(common::cube(1.0, [0.0; 3]))
+}
+
+#[test]
+fn closed_cube() {
+    let stl = cube();
+    let mesh = HalfEdgeMesh::from(&stl);
+
+    assert_eq!(mesh.vertex_count(), 8);
+    assert_eq!(mesh.facet_count(), 12);
+    assert_eq!(mesh.half_edge_count(), 36);
+
+    for half_edge in 0..mesh.half_edge_count() {
+        let twin = mesh.twin(half_edge).unwrap();
+
+        assert_eq!(mesh.twin(twin), Some(half_edge));
+        assert_eq!(mesh.origin(twin), mesh.target(half_edge));
+        assert_eq!(mesh.next(mesh.prev(half_edge)), half_edge);
+        assert_ne!(mesh.facet(twin), mesh.facet(half_edge));
+    }
+
+    for facet in 0..mesh.facet_count() {
+        assert_eq!(mesh.facet_neighbors(facet).count(), 3);
+    }
+
+    assert!(mesh.boundary_loops().is_empty());
+    assert_eq!(mesh.to_stl_file(), stl);
+}
+
+#[test]
+fn one_ring() {
+    let mesh = HalfEdgeMesh::new(&cube());
+
+    // vertex 0 is the corner at the origin
+    assert_eq!(mesh.point(0), point([0., 0., 0.]));
+
+    let mut ring = mesh
+        .vertex_one_ring(0)
+        .into_iter()
+        .map(|v| {
+            let p = mesh.point(v);
+            [p.x as u8, p.y as u8, p.z as u8]
+        })
+        .collect::<Vec<_>>();
+    ring.sort_unstable();
+
+    assert_eq!(
+        ring,
+        vec![
+            [0, 0, 1],
+            [0, 1, 0],
+            [0, 1, 1],
+            [1, 0, 0],
+            [1, 0, 1],
+            [1, 1, 0]
+        ]
+    );
+
+    for &half_edge in mesh.outgoing(0) {
+        assert_eq!(mesh.origin(half_edge), 0);
+    }
+}
+
+#[test]
+fn open_cube() {
+    let mut stl = cube();
+    stl.retain_triangles(|t| !(t.v0.z == 1.0 && t.v1.z == 1.0 && t.v2.z == 1.0));
+
+    let mesh = HalfEdgeMesh::new(&stl);
+    let loops = mesh.boundary_loops();
+
+    assert_eq!(loops.len(), 1);
+    assert_eq!(loops[0].len(), 4);
+    assert!(loops[0].iter().all(|&v| mesh.point(v).z == 1.0));
+    assert_eq!(mesh.boundary_half_edges().count(), 4);
+}
+
+#[test]
+fn non_manifold_edges_have_no_twin() {
+    let (a, b) = (point([0., 0., 0.]), point([1., 0., 0.]));
+
+    let stl = StlFile::from_triangles(vec![
+        triangle(a, b, point([0., 1., 0.])),
+        triangle(b, a, point([0., -1., 0.])),
+        triangle(b, a, point([0., 0., 1.])),
+    ]);
+    let mesh = HalfEdgeMesh::new(&stl);
+
+    assert!((0..mesh.half_edge_count()).all(|h| mesh.twin(h).is_none()));
+    assert_eq!(mesh.facet_neighbors(0).count(), 0);
+}
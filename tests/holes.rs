@@ -0,0 +1,114 @@
+mod common;
+
+use common::{point, quad, triangle, FACES};
+use stl::StlFile;
+
+/// A unit cube without the faces at the given indices into `FACES`
+fn open_cube(missing: &[usize]) -> StlFile {
+    FACES
+        .iter()
+        .enumerate()
+        .filter(|(face, _)| !missing.contains(face))
+        .flat_map(|(_, face)| quad(face.map(point)))
+        .collect()
+}
+
+#[test]
+fn closed_mesh_has_no_loops() {
+    let mut stl = open_cube(&[]);
+
+    assert!(stl.boundary_loops().is_empty());
+    assert_eq!(stl.fill_holes(usize::MAX), 0);
+    assert_eq!(stl.facet_count(), 12);
+}
+
+#[test]
+fn boundary_loops_follow_winding() {
+    let stl = open_cube(&[1]);
+    let loops = stl.boundary_loops();
+
+    assert_eq!(loops.len(), 1);
+    assert_eq!(
+        loops[0],
+        [
+            point([1., 0., 1.]),
+            point([0., 0., 1.]),
+            point([0., 1., 1.]),
+            point([1., 1., 1.]),
+        ]
+    );
+}
+
+#[test]
+fn fills_square_hole() {
+    let mut stl = open_cube(&[1]);
+
+    assert_eq!(stl.fill_holes(4), 1);
+    assert_eq!(stl.facet_count(), 12);
+
+    let report = stl.topology_report();
+    assert!(report.is_watertight());
+    assert!(report.is_consistently_oriented());
+    assert!((stl.signed_volume() - 1.0).abs() < 1e-9);
+
+    for facet in 10..12 {
+        let normal = stl.triangle(facet).unwrap().normal;
+        assert_eq!([normal.i, normal.j, normal.k], [0.0, 0.0, 1.0]);
+    }
+}
+
+#[test]
+fn fills_non_planar_hole() {
+    // the top and front faces leave a single hole bent over an edge
+    let mut stl = open_cube(&[1, 2]);
+    assert_eq!(stl.boundary_loops()[0].len(), 6);
+
+    assert_eq!(stl.fill_holes(6), 1);
+    assert_eq!(stl.facet_count(), 12);
+
+    let report = stl.topology_report();
+    assert!(report.is_watertight());
+    assert!(report.is_consistently_oriented());
+    assert!(stl.signed_volume() > 0.0);
+}
+
+#[test]
+fn fills_each_hole() {
+    let mut stl = open_cube(&[0, 1]);
+    assert_eq!(stl.boundary_loops().len(), 2);
+
+    assert_eq!(stl.fill_holes(4), 2);
+    assert!(stl.topology_report().is_watertight());
+    assert!((stl.signed_volume() - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn large_holes_are_left_open() {
+    let mut stl = open_cube(&[1, 2]);
+
+    assert_eq!(stl.fill_holes(5), 0);
+    assert_eq!(stl.facet_count(), 8);
+    assert_eq!(stl.boundary_loops().len(), 1);
+}
+
+#[test]
+fn open_surface_is_capped() {
+    let mut stl: StlFile = vec![
+        triangle(
+            point([0., 0., 0.]),
+            point([1., 0., 0.]),
+            point([1., 1., 0.]),
+        ),
+        triangle(
+            point([0., 0., 0.]),
+            point([1., 1., 0.]),
+            point([0., 1., 0.]),
+        ),
+    ]
+    .into_iter()
+    .collect();
+
+    assert_eq!(stl.fill_holes(4), 1);
+    assert!(stl.topology_report().is_watertight());
+    assert_eq!(stl.triangle(2).unwrap().normal.k, -1.0);
+}
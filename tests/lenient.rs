@@ -0,0 +1,152 @@
+mod common;
+
+use common::ASCII;
+use stl::{ParseErrorKind, ParseOptions, ParseWarning, StlError, StlFile, Triangle};
+
+fn lenient(input: &[u8]) -> (StlFile, Vec<ParseWarning>) {
+    StlFile::parse_with_options(input, &ParseOptions::lenient()).unwrap()
+}
+
+#[test]
+fn strict_by_default() {
+    assert!(ParseOptions::default().strict);
+
+    let (stl, warnings) = StlFile::parse_with_options(ASCII, &ParseOptions::default()).unwrap();
+    assert_eq!(stl, StlFile::parse(ASCII).unwrap());
+    assert!(warnings.is_empty());
+}
+
+#[test]
+fn valid_input_is_unchanged() {
+    let (stl, warnings) = lenient(ASCII);
+
+    assert_eq!(stl, StlFile::parse(ASCII).unwrap());
+    assert!(warnings.is_empty());
+}
+
+#[test]
+fn uppercase_keywords() {
+    let input = String::from_utf8(ASCII.to_vec())
+        .unwrap()
+        .to_uppercase()
+        .replace("SOLID CUBE", "solid cube");
+
+    assert!(StlFile::parse(input.as_bytes()).is_err());
+
+    let (stl, warnings) = lenient(input.as_bytes());
+    assert_eq!(stl.facet_count(), 2);
+    assert_eq!(stl.name(), "cube");
+    assert_eq!(warnings, vec![ParseWarning::KeywordCase { offset: 13 }]);
+}
+
+#[test]
+fn missing_and_multi_word_names() {
+    let input = b"solid\n  facet normal 0 0 -1\n    outer loop\n      vertex 0 0 0\n      vertex 1 1 0\n      vertex 1 0 0\n    endloop\n  endfacet\nendsolid\n";
+    assert!(StlFile::parse(input).is_err());
+
+    let (stl, warnings) = lenient(input);
+    assert_eq!(stl.facet_count(), 1);
+    assert_eq!(stl.name(), "");
+    assert_eq!(warnings, vec![ParseWarning::MissingName { offset: 5 }]);
+
+    let (stl, warnings) = lenient(b"solid\tMy Part  v2 \nendsolid My Part v2\n");
+    assert_eq!(stl.name(), "My Part  v2");
+    assert!(warnings.is_empty());
+
+    let (stl, _) = lenient(b"solid facet normal 0 0 1 outer loop vertex 0 0 0 vertex 1 0 0 vertex 0 1 0 endloop endfacet endsolid");
+    assert_eq!(stl.name(), "");
+    assert_eq!(stl.facet_count(), 1);
+}
+
+#[test]
+fn trailing_garbage() {
+    let mut input = ASCII.to_vec();
+    input.extend_from_slice(b"\x00\x00garbage");
+
+    let (stl, warnings) = StlFile::parse_with_options(&input, &ParseOptions::default()).unwrap();
+    assert_eq!(stl.facet_count(), 2);
+    assert_eq!(
+        warnings,
+        vec![ParseWarning::TrailingContent {
+            offset: ASCII.len()
+        }]
+    );
+
+    let mut input = ASCII[..ASCII.len() - 1].to_vec();
+    input.extend_from_slice(b" exported by tool\n");
+
+    let (_, warnings) = lenient(&input);
+    assert!(warnings.is_empty());
+}
+
+#[test]
+fn polygons_are_triangulated() {
+    let input = b"solid quad
+  facet normal 0 0 1
+    outer loop
+      vertex 0 0 0
+      vertex 1 0 0
+      vertex 1 1 0
+      vertex 0 1 0
+      vertex -1 0.5 0
+    endloop
+  endfacet
+endsolid quad
+";
+
+    assert!(StlFile::parse(input).is_err());
+
+    let (stl, warnings) = lenient(input);
+    assert_eq!(stl.facet_count(), 3);
+    assert_eq!(
+        warnings,
+        vec![ParseWarning::Polygon {
+            offset: 53,
+            vertices: 5
+        }]
+    );
+
+    let triangles: Vec<Triangle> = stl.triangles().collect();
+    assert!(triangles.iter().all(|t| t.v0 == triangles[0].v0));
+    assert_eq!(triangles[1].v1, triangles[0].v2);
+    assert_eq!(triangles[2].v1, triangles[1].v2);
+}
+
+#[test]
+fn malformed_ascii_is_still_an_error() {
+    let facet = "  facet normal 0 0 -1\n    outer loop\n      vertex 0 0 0\n      vertex 1 1 0\n      vertex 1 0 0\n    endloop\n  endfacet\n";
+    let input = format!("solid foo\n{}garbage", facet.repeat(20));
+
+    assert!(StlFile::parse_with_options(input.as_bytes(), &ParseOptions::lenient()).is_err());
+
+    let input = format!(
+        "solid foo\n{}",
+        facet.replace("vertex 1 0 0", "vertex 1 0 X")
+    );
+
+    match StlFile::parse_with_options(input.as_bytes(), &ParseOptions::lenient()) {
+        Err(StlError::ParseError(err)) => assert_eq!(
+            *err.kind(),
+            ParseErrorKind::InvalidFloat {
+                found: "X".to_owned()
+            }
+        ),
+        result => panic!("expected invalid float, got {:?}", result),
+    }
+}
+
+#[test]
+fn malformed_uppercase_ascii_is_still_an_error() {
+    let facet = "  FACET NORMAL 0 0 -1\n    OUTER LOOP\n      VERTEX 0 0 0\n      VERTEX 1 1 0\n      VERTEX 1 0 X\n    ENDLOOP\n  ENDFACET\n";
+    let input = format!("SOLID foo\n{}ENDSOLID foo\n", facet.repeat(5));
+
+    match StlFile::parse_with_options(input.as_bytes(), &ParseOptions::lenient()) {
+        Err(StlError::ParseError(err)) => assert_eq!(
+            *err.kind(),
+            ParseErrorKind::InvalidFloat {
+                found: "X".to_owned()
+            }
+        ),
+        result => panic!("expected invalid float, got {:?}", result),
+    }
+}
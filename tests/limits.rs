@@ -0,0 +1,142 @@
+mod common;
+
+use common::{binary, ASCII};
+use stl::{LimitExceeded, ParseOptions, StlError, StlFile, StlReader, Triangle};
+
+fn limit(err: StlError) -> LimitExceeded {
+    match err {
+        StlError::LimitExceeded(limit) => limit,
+        err => panic!("expected exceeded limit, got {:?}", err),
+    }
+}
+
+fn parse(input: &[u8], options: ParseOptions) -> Result<StlFile, LimitExceeded> {
+    StlFile::parse_with_options(input, &options)
+        .map(|(stl, _)| stl)
+        .map_err(limit)
+}
+
+fn read(input: &[u8], options: ParseOptions) -> Result<Vec<Triangle>, LimitExceeded> {
+    StlReader::with_options(input, options)
+        .and_then(|reader| reader.collect::<Result<Vec<_>, _>>())
+        .map_err(limit)
+}
+
+#[test]
+fn unlimited_by_default() {
+    let options = ParseOptions::default();
+
+    assert_eq!(options.max_facets, None);
+    assert_eq!(options.max_input_len, None);
+    assert_eq!(options.max_allocation, None);
+
+    assert_eq!(parse(ASCII, options).unwrap().facet_count(), 2);
+    assert_eq!(parse(&binary(), options).unwrap().facet_count(), 2);
+}
+
+#[test]
+fn max_facets() {
+    let options = |max_facets| ParseOptions {
+        max_facets: Some(max_facets),
+        ..ParseOptions::default()
+    };
+
+    for input in &[ASCII, &binary()] {
+        assert_eq!(
+            parse(input, options(1)).unwrap_err(),
+            LimitExceeded::FacetCount { limit: 1 }
+        );
+        assert_eq!(parse(input, options(2)).unwrap().facet_count(), 2);
+
+        assert_eq!(
+            read(input, options(1)).unwrap_err(),
+            LimitExceeded::FacetCount { limit: 1 }
+        );
+        assert_eq!(read(input, options(2)).unwrap().len(), 2);
+    }
+}
+
+#[test]
+fn max_input_len() {
+    for input in &[ASCII, &binary()] {
+        let options = |max_input_len| ParseOptions {
+            max_input_len: Some(max_input_len),
+            ..ParseOptions::default()
+        };
+
+        let limit = input.len() - 1;
+
+        assert_eq!(
+            parse(input, options(limit)).unwrap_err(),
+            LimitExceeded::InputLen { limit }
+        );
+        assert_eq!(
+            read(input, options(limit)).unwrap_err(),
+            LimitExceeded::InputLen { limit }
+        );
+
+        assert_eq!(parse(input, options(input.len())).unwrap().facet_count(), 2);
+        assert_eq!(read(input, options(input.len())).unwrap().len(), 2);
+    }
+}
+
+#[test]
+fn max_input_len_in_reader_beyond_sniffed_prefix() {
+    let mut input = ASCII.to_vec();
+    input.resize(input.len() + 4096, b' ');
+
+    let options = ParseOptions {
+        max_input_len: Some(2048),
+        ..ParseOptions::default()
+    };
+
+    assert_eq!(
+        read(&input, options).unwrap_err(),
+        LimitExceeded::InputLen { limit: 2048 }
+    );
+}
+
+#[test]
+fn max_allocation() {
+    let options = |max_allocation| ParseOptions {
+        max_allocation: Some(max_allocation),
+        ..ParseOptions::default()
+    };
+
+    let limit = 2 * ParseOptions::FACET_SIZE - 1;
+
+    for input in &[ASCII, &binary()] {
+        assert_eq!(
+            parse(input, options(limit)).unwrap_err(),
+            LimitExceeded::Allocation { limit }
+        );
+        assert_eq!(parse(input, options(limit + 1)).unwrap().facet_count(), 2);
+
+        // the reader does not allocate per facet
+        assert_eq!(read(input, options(0)).unwrap().len(), 2);
+    }
+}
+
+#[test]
+fn huge_declared_facet_count() {
+    let mut buffer = vec![0; 84];
+    buffer[80..84].copy_from_slice(&u32::MAX.to_le_bytes());
+
+    assert!(matches!(
+        StlFile::parse_binary(&buffer),
+        Err(StlError::ParseError(..))
+    ));
+
+    let options = ParseOptions {
+        max_facets: Some(1000),
+        ..ParseOptions::lenient()
+    };
+
+    // only the facets actually present count towards the limit
+    assert_eq!(parse(&buffer, options).unwrap().facet_count(), 0);
+
+    assert_eq!(
+        read(&buffer, options).unwrap_err(),
+        LimitExceeded::FacetCount { limit: 1000 }
+    );
+}
@@ -0,0 +1,196 @@
+mod common;
+
+use common::{binary, ASCII};
+use stl::{ParseErrorKind, ParseOptions, StlError, StlFile, StlFormat};
+
+/// Run every public entry point over `buffer`; any panic fails the test
+fn parse_all(buffer: &[u8]) {
+    let _ = StlFile::parse(buffer);
+    let _ = StlFile::parse_ascii(buffer);
+    let _ = StlFile::parse_binary(buffer);
+    let _ = StlFile::parse_with_options(buffer, &ParseOptions::lenient());
+}
+
+/// Deterministic xorshift generator so failures are reproducible
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+fn kind(err: StlError) -> ParseErrorKind {
+    match err {
+        StlError::ParseError(err) => err.kind().clone(),
+        err => panic!("expected parse error, got {:?}", err),
+    }
+}
+
+#[test]
+fn valid_input_parses() {
+    assert_eq!(StlFile::parse(ASCII).unwrap().facet_count(), 2);
+    assert_eq!(StlFile::parse(&binary()).unwrap().facet_count(), 2);
+}
+
+#[test]
+fn every_ascii_truncation_is_an_error() {
+    let end = ASCII.len() - b"endsolid cube\n".len();
+
+    for len in 0..end {
+        assert!(StlFile::parse_ascii(&ASCII[..len]).is_err(), "len {}", len);
+        parse_all(&ASCII[..len]);
+    }
+}
+
+#[test]
+fn every_binary_truncation_is_an_error() {
+    let binary = binary();
+
+    for len in 0..binary.len() {
+        assert!(
+            StlFile::parse_binary(&binary[..len]).is_err(),
+            "len {}",
+            len
+        );
+        parse_all(&binary[..len]);
+    }
+}
+
+#[test]
+fn truncated_binary_reports_facet() {
+    let binary = binary();
+
+    assert_eq!(
+        kind(StlFile::parse_binary(&binary[..100]).unwrap_err()),
+        ParseErrorKind::TruncatedFacet { facet: 0 }
+    );
+    assert_eq!(
+        kind(StlFile::parse_binary(&binary[..40]).unwrap_err()),
+        ParseErrorKind::TruncatedHeader
+    );
+    assert_eq!(
+        kind(StlFile::parse_binary(&binary[..134]).unwrap_err()),
+        ParseErrorKind::FacetCountMismatch {
+            expected: 2,
+            found: 1
+        }
+    );
+}
+
+#[test]
+fn huge_declared_facet_count() {
+    let mut buffer = vec![0; 84];
+    buffer[80..].copy_from_slice(&u32::MAX.to_le_bytes());
+
+    assert_eq!(
+        kind(StlFile::parse_binary(&buffer).unwrap_err()),
+        ParseErrorKind::FacetCountMismatch {
+            expected: u32::MAX,
+            found: 0
+        }
+    );
+}
+
+#[test]
+fn invalid_float() {
+    let input = b"solid a\nfacet normal 0 0 1e\n";
+
+    assert_eq!(
+        kind(StlFile::parse_ascii(input).unwrap_err()),
+        ParseErrorKind::InvalidFloat {
+            found: "1e".to_owned()
+        }
+    );
+}
+
+#[test]
+fn missing_name() {
+    for input in &[&b"solid"[..], b"solid\nfacet normal 0 0 1\n"] {
+        let err = StlFile::parse_ascii(input).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "expected `solid <name>` at line 1, column 1: `solid`"
+        );
+        assert_eq!(kind(err), ParseErrorKind::MissingName);
+    }
+}
+
+#[test]
+fn random_garbage() {
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+
+    for _ in 0..2000 {
+        let len = (rng.next() % 300) as usize;
+        let buffer: Vec<u8> = (0..len).map(|_| rng.next() as u8).collect();
+        parse_all(&buffer);
+
+        let mut prefixed = b"solid ".to_vec();
+        prefixed.extend_from_slice(&buffer);
+        parse_all(&prefixed);
+    }
+}
+
+#[test]
+fn mutated_ascii() {
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+    let alphabet = b" \n\tsolidfacetnormalvertexendloop0123456789.-+eE";
+
+    for _ in 0..2000 {
+        let mut buffer = ASCII.to_vec();
+
+        for _ in 0..(rng.next() % 4 + 1) {
+            let idx = (rng.next() as usize) % buffer.len();
+            match rng.next() % 3 {
+                0 => buffer[idx] = alphabet[(rng.next() as usize) % alphabet.len()],
+                1 => {
+                    buffer.remove(idx);
+                }
+                _ => buffer.insert(idx, alphabet[(rng.next() as usize) % alphabet.len()]),
+            }
+        }
+
+        parse_all(&buffer);
+    }
+}
+
+#[test]
+fn mutated_binary() {
+    let mut rng = Rng(0xdead_beef_cafe_f00d);
+    let original = binary();
+
+    for _ in 0..2000 {
+        let mut buffer = original.clone();
+
+        for _ in 0..(rng.next() % 4 + 1) {
+            let idx = (rng.next() as usize) % buffer.len();
+            buffer[idx] = rng.next() as u8;
+        }
+
+        parse_all(&buffer);
+    }
+}
+
+#[test]
+fn binary_with_solid_header() {
+    let mut buffer = binary();
+    buffer[..18].copy_from_slice(b"solid facet normal");
+
+    assert_eq!(StlFormat::detect(&buffer), StlFormat::Binary);
+    assert_eq!(StlFile::parse(&buffer).unwrap().facet_count(), 2);
+}
+
+#[test]
+fn misdetected_format_falls_back() {
+    // the declared facet count no longer matches the length, so detection
+    // relies on the `solid` prefix and guesses wrong
+    let mut buffer = binary();
+    buffer[..5].copy_from_slice(b"solid");
+    buffer.extend_from_slice(&[0; 7]);
+
+    assert_eq!(StlFormat::detect(&buffer), StlFormat::Ascii);
+    assert_eq!(StlFile::parse(&buffer).unwrap().facet_count(), 2);
+}
@@ -0,0 +1,106 @@
+mod common;
+
+use common::cube;
+use stl::StlFile;
+
+fn assert_close(a: f64, b: f64) {
+    assert!((a - b).abs() < 1e-9, "{} != {}", a, b);
+}
+
+fn assert_cube(stl: &StlFile, size: f64, center: [f64; 3]) {
+    let volume = size.powi(3);
+    let props = stl.mass_properties();
+
+    assert_close(props.surface_area, 6.0 * size * size);
+    assert_close(props.signed_volume, volume);
+
+    let centroid = props.centroid.unwrap();
+    assert_close(centroid.x, center[0]);
+    assert_close(centroid.y, center[1]);
+    assert_close(centroid.z, center[2]);
+
+    let inertia = props.inertia_tensor.unwrap();
+    for (i, row) in inertia.iter().enumerate() {
+        for (j, &value) in row.iter().enumerate() {
+            let expected = if i == j {
+                volume * size * size / 6.0
+            } else {
+                0.0
+            };
+            assert_close(value, expected);
+        }
+    }
+}
+
+#[test]
+fn cube_properties() {
+    let stl = StlFile::from_triangles(cube(2.0, [10.0, -3.0, 5.0]));
+
+    assert_cube(&stl, 2.0, [11.0, -2.0, 6.0]);
+
+    assert_close(stl.surface_area(), 24.0);
+    assert_close(stl.signed_volume(), 8.0);
+    assert_close(stl.volume(), 8.0);
+    assert_eq!(stl.centroid(), stl.mass_properties().centroid);
+    assert_eq!(stl.inertia_tensor(), stl.mass_properties().inertia_tensor);
+}
+
+#[test]
+fn inside_out_cube() {
+    let triangles = cube(2.0, [0.0, 0.0, 0.0]).into_iter().map(|mut t| {
+        t.reverse_winding();
+        t
+    });
+    let stl = StlFile::from_triangles(triangles);
+    let outward = StlFile::from_triangles(cube(2.0, [0.0, 0.0, 0.0]));
+
+    assert_close(stl.signed_volume(), -8.0);
+    assert_close(stl.volume(), 8.0);
+    assert_eq!(stl.centroid(), outward.centroid());
+
+    let (inverted, outward) = (
+        stl.inertia_tensor().unwrap(),
+        outward.inertia_tensor().unwrap(),
+    );
+    for i in 0..3 {
+        for j in 0..3 {
+            assert_close(inverted[i][j], outward[i][j]);
+        }
+    }
+}
+
+#[test]
+fn no_enclosed_volume() {
+    let stl = StlFile::from_triangles(cube(1.0, [0.0, 0.0, 0.0]).into_iter().take(2));
+
+    assert_close(stl.surface_area(), 1.0);
+    assert_close(stl.signed_volume(), 0.0);
+    assert_eq!(stl.centroid(), None);
+    assert_eq!(stl.inertia_tensor(), None);
+
+    let empty = StlFile::new();
+    assert_close(empty.surface_area(), 0.0);
+    assert_eq!(empty.centroid(), None);
+}
+
+#[test]
+fn islands() {
+    let triangles = cube(1.0, [0.0, 0.0, 0.0])
+        .into_iter()
+        .chain(cube(3.0, [5.0, 0.0, 0.0]));
+    let stl = StlFile::from_triangles(triangles);
+
+    assert_close(stl.volume(), 28.0);
+
+    let islands = stl.island_mass_properties();
+    assert_eq!(islands.len(), 2);
+
+    let small = StlFile::from_triangles(cube(1.0, [0.0, 0.0, 0.0]));
+    let large = StlFile::from_triangles(cube(3.0, [5.0, 0.0, 0.0]));
+    assert_cube(&small, 1.0, [0.5, 0.5, 0.5]);
+    assert_cube(&large, 3.0, [6.5, 1.5, 1.5]);
+
+    assert_eq!(islands[0], small.mass_properties());
+    assert_eq!(islands[1], large.mass_properties());
+    assert_eq!(stl.split_islands(), vec![small, large]);
+}
@@ -0,0 +1,142 @@
+use stl::{Normal, NormalDeviation, ParseOptions, Point, StlFile, StlReader, Triangle};
+
+const ASCII: &[u8] = b"solid cube
+  facet normal 0 0 0
+    outer loop
+      vertex 0 0 0
+      vertex 1 1 0
+      vertex 1 0 0
+    endloop
+  endfacet
+  facet normal 0 0 -1
+    outer loop
+      vertex 0 0 1
+      vertex 2 0 1
+      vertex 2 2 1
+    endloop
+  endfacet
+  facet normal 0.1 0 0.99
+    outer loop
+      vertex 0 0 2
+      vertex 1 0 2
+      vertex 1 1 2
+    endloop
+  endfacet
+  facet normal 1 0 0
+    outer loop
+      vertex 0 0 3
+      vertex 1 0 3
+      vertex 2 0 3
+    endloop
+  endfacet
+endsolid cube
+";
+
+fn normal(i: f32, j: f32, k: f32) -> Normal {
+    Normal { i, j, k }
+}
+
+#[test]
+fn computed_normal() {
+    let triangle = Triangle {
+        normal: normal(0.0, 0.0, 0.0),
+        v0: Point {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        },
+        v1: Point {
+            x: 0.0,
+            y: 3.0,
+            z: 0.0,
+        },
+        v2: Point {
+            x: 0.0,
+            y: 0.0,
+            z: 3.0,
+        },
+    };
+
+    assert_eq!(triangle.computed_normal(), Some(normal(1.0, 0.0, 0.0)));
+
+    let degenerate = Triangle {
+        v2: triangle.v1,
+        ..triangle
+    };
+    assert_eq!(degenerate.computed_normal(), None);
+}
+
+#[test]
+fn recompute_normals() {
+    let mut stl = StlFile::parse(ASCII).unwrap();
+    let vertices = stl.vertex_buffer().to_vec();
+
+    stl.recompute_normals();
+
+    assert_eq!(
+        stl.normals(),
+        &[
+            normal(0.0, 0.0, -1.0),
+            normal(0.0, 0.0, 1.0),
+            normal(0.0, 0.0, 1.0),
+            normal(0.0, 0.0, 0.0),
+        ]
+    );
+    assert_eq!(stl.vertex_buffer(), vertices.as_slice());
+    assert!(stl.normal_deviation_report(0.0).is_empty());
+}
+
+#[test]
+fn deviation_report() {
+    let stl = StlFile::parse(ASCII).unwrap();
+
+    let report = stl.normal_deviation_report(10.0);
+    assert_eq!(
+        report,
+        vec![
+            NormalDeviation {
+                facet: 0,
+                angle: None
+            },
+            NormalDeviation {
+                facet: 1,
+                angle: Some(180.0)
+            },
+        ]
+    );
+
+    let report = stl.normal_deviation_report(1.0);
+    assert_eq!(report.len(), 3);
+    assert_eq!(report[2].facet, 2);
+    assert!((report[2].angle.unwrap() - 5.77).abs() < 0.01);
+}
+
+#[test]
+fn recompute_while_parsing() {
+    let options = ParseOptions {
+        recompute_normals: true,
+        ..ParseOptions::default()
+    };
+
+    let mut expected = StlFile::parse(ASCII).unwrap();
+    expected.recompute_normals();
+
+    let (stl, _) = StlFile::parse_with_options(ASCII, &options).unwrap();
+    assert_eq!(stl, expected);
+
+    let mut buffer = Vec::new();
+    StlFile::parse(ASCII)
+        .unwrap()
+        .write_binary(&mut buffer)
+        .unwrap();
+    let (stl, _) = StlFile::parse_with_options(&buffer, &options).unwrap();
+    assert_eq!(stl.normals(), expected.normals());
+
+    let triangles = StlReader::with_options(ASCII, options)
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(triangles, expected.triangles().collect::<Vec<_>>());
+
+    assert!(!ParseOptions::default().recompute_normals);
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use common::{point, triangle, FACES};
+use stl::{StlFile, Triangle};
+
+/// A unit cube offset along the x axis, with normals matching its winding
+fn cube(offset: f32) -> Vec<Triangle> {
+    common::cube(1.0, [offset, 0.0, 0.0])
+        .into_iter()
+        .map(with_normal)
+        .collect()
+}
+
+fn with_normal(mut triangle: Triangle) -> Triangle {
+    triangle.normal = triangle.computed_normal().unwrap();
+    triangle
+}
+
+fn flipped(mut triangle: Triangle) -> Triangle {
+    triangle.reverse_winding();
+    triangle
+}
+
+#[test]
+fn consistent_cube_is_unchanged() {
+    let mut stl = StlFile::from_triangles(cube(0.0));
+
+    assert_eq!(stl.orient_consistently(), 0);
+    assert_eq!(stl, StlFile::from_triangles(cube(0.0)));
+}
+
+#[test]
+fn inside_out_cube() {
+    let mut stl = StlFile::from_triangles(cube(0.0).into_iter().map(flipped));
+
+    assert_eq!(stl.orient_consistently(), 12);
+    assert_eq!(stl, StlFile::from_triangles(cube(0.0)));
+}
+
+#[test]
+fn partially_flipped_cube() {
+    let triangles =
+        cube(0.0)
+            .into_iter()
+            .enumerate()
+            .map(|(facet, t)| if facet % 5 == 1 { flipped(t) } else { t });
+    let mut stl = StlFile::from_triangles(triangles);
+
+    assert_eq!(stl.orient_consistently(), 3);
+    assert_eq!(stl, StlFile::from_triangles(cube(0.0)));
+}
+
+#[test]
+fn components_are_oriented_independently() {
+    let triangles = cube(0.0)
+        .into_iter()
+        .chain(cube(5.0).into_iter().map(flipped));
+    let mut stl = StlFile::from_triangles(triangles);
+
+    assert_eq!(stl.orient_consistently(), 12);
+    assert_eq!(
+        stl,
+        StlFile::from_triangles(cube(0.0).into_iter().chain(cube(5.0)))
+    );
+}
+
+#[test]
+fn open_surface_is_made_consistent() {
+    let [a, b, c, d] = FACES[0].map(point);
+    let mut stl = StlFile::from_triangles(vec![triangle(a, b, c), triangle(a, d, c)]);
+
+    assert_eq!(stl.orient_consistently(), 1);
+    assert_eq!(
+        stl,
+        StlFile::from_triangles(vec![triangle(a, b, c), triangle(a, c, d)])
+    );
+}
+
+#[test]
+fn open_sheet_is_not_flipped() {
+    // wound clockwise when seen from the origin, so its volume relative to
+    // the origin is negative
+    let sheet = vec![triangle(
+        point([0., 0., 1.]),
+        point([0., 1., 1.]),
+        point([1., 0., 1.]),
+    )];
+    let mut stl = StlFile::from_triangles(sheet.clone());
+
+    assert_eq!(stl.orient_consistently(), 0);
+    assert_eq!(stl, StlFile::from_triangles(sheet));
+}
@@ -0,0 +1,82 @@
+#![cfg(feature = "rayon")]
+
+use stl::{AsciiOptions, StlFile};
+
+/// An ASCII file with several solids, each large enough to be split
+fn large_ascii() -> Vec<u8> {
+    let mut buffer = Vec::new();
+
+    for solid in 0..3 {
+        buffer.extend_from_slice(format!("solid part{}\n", solid).as_bytes());
+
+        for i in 0..5000 {
+            let x = i as f32 * 0.25;
+            buffer.extend_from_slice(
+                format!(
+                    "facet normal 0 0 1\nouter loop\nvertex {0} 0 {1}\nvertex {0} 1 {1}\nvertex 1 {0} {1}\nendloop\nendfacet\n",
+                    x, solid
+                )
+                .as_bytes(),
+            );
+        }
+
+        buffer.extend_from_slice(format!("endsolid part{}\n", solid).as_bytes());
+    }
+
+    buffer
+}
+
+#[test]
+fn ascii_matches_sequential() {
+    let buffer = large_ascii();
+
+    let stl = StlFile::parse(&buffer).unwrap();
+    assert_eq!(stl.facet_count(), 15000);
+    assert_eq!(StlFile::par_parse(&buffer).unwrap(), stl);
+    assert_eq!(StlFile::par_parse_ascii(&buffer).unwrap(), stl);
+}
+
+#[test]
+fn binary_matches_sequential() {
+    let mut stl = StlFile::parse(&large_ascii()).unwrap();
+    stl.set_header([b'h'; 80]);
+    for (i, attribute) in stl.attributes_mut().iter_mut().enumerate() {
+        *attribute = i as u16;
+    }
+
+    let mut buffer = Vec::new();
+    stl.write_binary(&mut buffer).unwrap();
+
+    let parsed = StlFile::parse(&buffer).unwrap();
+    assert_eq!(StlFile::par_parse(&buffer).unwrap(), parsed);
+    assert_eq!(StlFile::par_parse_binary(&buffer).unwrap(), parsed);
+}
+
+#[test]
+fn ascii_reports_earliest_error() {
+    let mut buffer = large_ascii();
+
+    for offset in &[buffer.len() / 2, buffer.len() / 3] {
+        let idx = buffer[*offset..]
+            .windows(6)
+            .position(|w| w == b"vertex")
+            .unwrap();
+        buffer[offset + idx] = b'V';
+    }
+
+    let expected = StlFile::parse_ascii(&buffer).unwrap_err().to_string();
+    assert_eq!(
+        StlFile::par_parse_ascii(&buffer).unwrap_err().to_string(),
+        expected
+    );
+}
+
+#[test]
+fn small_inputs() {
+    let stl = StlFile::parse(b"solid a\nendsolid a\n").unwrap();
+    assert_eq!(StlFile::par_parse(b"solid a\nendsolid a\n").unwrap(), stl);
+
+    let mut buffer = Vec::new();
+    StlFile::write_ascii_solids(&[stl], &mut buffer, &AsciiOptions::default()).unwrap();
+    assert_eq!(StlFile::par_parse(&buffer).unwrap().facet_count(), 0);
+}
@@ -0,0 +1,136 @@
+mod common;
+
+use std::io::Cursor;
+
+use common::{binary, ASCII};
+use stl::{ParseErrorKind, StlError, StlFile, StlFormat, StlReader, StlWriter, Triangle};
+
+fn read_all(input: &[u8]) -> Result<Vec<Triangle>, StlError> {
+    StlReader::new(input)?.collect()
+}
+
+#[test]
+fn ascii_matches_parser() {
+    let reader = StlReader::new(ASCII).unwrap();
+    assert_eq!(reader.format(), StlFormat::Ascii);
+
+    let expected: Vec<Triangle> = StlFile::parse(ASCII).unwrap().triangles().collect();
+    assert_eq!(read_all(ASCII).unwrap(), expected);
+}
+
+#[test]
+fn binary_matches_parser() {
+    let binary = binary();
+
+    let reader = StlReader::new(binary.as_slice()).unwrap();
+    assert_eq!(reader.format(), StlFormat::Binary);
+
+    let expected: Vec<Triangle> = StlFile::parse(&binary).unwrap().triangles().collect();
+    assert_eq!(read_all(&binary).unwrap(), expected);
+}
+
+#[test]
+fn binary_larger_than_sniff_buffer() {
+    let triangle = StlFile::parse_ascii(ASCII)
+        .unwrap()
+        .triangles()
+        .next()
+        .unwrap();
+
+    let mut binary = vec![0; 80];
+    binary.extend_from_slice(&1000u32.to_le_bytes());
+    for _ in 0..1000 {
+        binary.extend_from_slice(&[0; 12]);
+        for v in &[triangle.v0, triangle.v1, triangle.v2] {
+            binary.extend_from_slice(&v.x.to_le_bytes());
+            binary.extend_from_slice(&v.y.to_le_bytes());
+            binary.extend_from_slice(&v.z.to_le_bytes());
+        }
+        binary.extend_from_slice(&[0; 2]);
+    }
+
+    let triangles = read_all(&binary).unwrap();
+    assert_eq!(triangles.len(), 1000);
+    assert!(triangles.iter().all(|t| t.v1 == triangle.v1));
+}
+
+#[test]
+fn truncated_input_is_an_error() {
+    let binary = binary();
+
+    for len in 0..binary.len() {
+        assert!(read_all(&binary[..len]).is_err(), "len {}", len);
+    }
+
+    let end = ASCII.len() - b"endsolid cube\n".len();
+
+    for len in 0..end {
+        assert!(read_all(&ASCII[..len]).is_err(), "len {}", len);
+    }
+}
+
+#[test]
+fn error_location() {
+    let input = b"solid a\nfacet normal 0 0 1\nouter lop\n";
+
+    match read_all(input).unwrap_err() {
+        StlError::ParseError(err) => {
+            assert_eq!(
+                err.kind(),
+                &ParseErrorKind::ExpectedKeyword {
+                    expected: "loop",
+                    found: "lop".to_owned()
+                }
+            );
+            assert_eq!(err.offset(), 33);
+
+            let location = err.location().unwrap();
+            assert_eq!((location.line, location.column), (3, 7));
+            assert_eq!(location.snippet, "outer lop");
+        }
+        err => panic!("{:?}", err),
+    }
+}
+
+#[test]
+fn stops_after_error() {
+    let mut reader = StlReader::new(&b"solid a\nfacet normal x"[..]).unwrap();
+
+    assert!(reader.next().unwrap().is_err());
+    assert!(reader.next().is_none());
+}
+
+#[test]
+fn attributes_are_streamed() {
+    let mut stl = StlFile::parse(ASCII).unwrap();
+    stl.attributes_mut()[0] = 0x801f;
+
+    let mut binary = Vec::new();
+    stl.write_binary(&mut binary).unwrap();
+
+    let mut reader = StlReader::new(binary.as_slice()).unwrap();
+    assert_eq!(reader.next_with_attribute().unwrap().unwrap().1, 0x801f);
+    assert_eq!(reader.next().unwrap().unwrap(), stl.triangle(1).unwrap());
+    assert!(reader.next_with_attribute().is_none());
+
+    let mut writer = StlWriter::binary(Cursor::new(Vec::new())).unwrap();
+    for result in StlReader::new(binary.as_slice()).unwrap().with_attributes() {
+        let (triangle, attribute) = result.unwrap();
+        writer
+            .write_triangle_with_attribute(triangle, attribute)
+            .unwrap();
+    }
+    let copy = writer.finish().unwrap().into_inner();
+
+    assert_eq!(
+        StlFile::parse(&copy).unwrap().attributes(),
+        stl.attributes()
+    );
+
+    let attributes: Vec<u16> = StlReader::new(ASCII)
+        .unwrap()
+        .with_attributes()
+        .map(|result| result.unwrap().1)
+        .collect();
+    assert_eq!(attributes, vec![0; stl.facet_count() as usize]);
+}
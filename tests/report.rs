@@ -0,0 +1,116 @@
+mod common;
+
+use common::to_binary;
+use stl::{ParseOptions, ParseWarning, StlFile};
+
+/// One facet on each of two faces of a cube, so that tests can edit them
+/// independently
+const ASCII: &[u8] = b"solid cube
+  facet normal 0 0 -1
+    outer loop
+      vertex 0 0 0
+      vertex 1 1 0
+      vertex 1 0 0
+    endloop
+  endfacet
+  facet normal 0 0 1
+    outer loop
+      vertex 0 0 1
+      vertex 1 0 1
+      vertex 1 1 1
+    endloop
+  endfacet
+endsolid cube
+";
+
+fn report(input: &[u8], options: &ParseOptions) -> (StlFile, Vec<ParseWarning>) {
+    StlFile::parse_with_report(input, options).unwrap()
+}
+
+#[test]
+fn valid_input_has_no_warnings() {
+    let (stl, warnings) = report(ASCII, &ParseOptions::default());
+    assert_eq!(stl, StlFile::parse(ASCII).unwrap());
+    assert!(warnings.is_empty());
+
+    let (_, warnings) = report(&to_binary(ASCII), &ParseOptions::default());
+    assert!(warnings.is_empty());
+}
+
+#[test]
+fn binary_trailing_bytes() {
+    let mut buffer = to_binary(ASCII);
+    let len = buffer.len();
+    buffer.extend_from_slice(b"garbage");
+
+    let (stl, warnings) = report(&buffer, &ParseOptions::default());
+    assert_eq!(stl.facet_count(), 2);
+    assert_eq!(
+        warnings,
+        vec![ParseWarning::TrailingBytes {
+            offset: len,
+            len: 7
+        }]
+    );
+}
+
+#[test]
+fn binary_facet_count_mismatch() {
+    let mut buffer = to_binary(ASCII);
+    buffer[80..84].copy_from_slice(&5_u32.to_le_bytes());
+    buffer.extend_from_slice(&[0; 10]);
+
+    assert!(StlFile::parse_with_report(&buffer, &ParseOptions::default()).is_err());
+
+    let (stl, warnings) = report(&buffer, &ParseOptions::lenient());
+    assert_eq!(stl.facet_count(), 2);
+    assert_eq!(
+        warnings,
+        vec![
+            ParseWarning::FacetCountMismatch {
+                expected: 5,
+                found: 2
+            },
+            ParseWarning::TrailingBytes {
+                offset: 84 + 2 * 50,
+                len: 10
+            },
+        ]
+    );
+}
+
+#[test]
+fn suspicious_facets() {
+    let input = String::from_utf8(ASCII.to_vec())
+        .unwrap()
+        .replace("facet normal 0 0 -1", "facet normal 0 0 1")
+        .replace("vertex 1 1 1", "vertex 2 0 1")
+        .replace("vertex 1 0 0", "vertex 1 0 nan");
+
+    let (stl, warnings) = report(input.as_bytes(), &ParseOptions::default());
+    assert_eq!(stl.facet_count(), 2);
+    assert_eq!(
+        warnings,
+        vec![
+            ParseWarning::NonFinite { facet: 0 },
+            ParseWarning::ZeroArea { facet: 1 },
+        ]
+    );
+}
+
+#[test]
+fn flipped_and_zero_normals() {
+    let input = String::from_utf8(ASCII.to_vec())
+        .unwrap()
+        .replace("facet normal 0 0 -1", "facet normal 0 0 0")
+        .replace("facet normal 0 0 1", "facet normal 0 -1 0");
+
+    let (_, warnings) = report(input.as_bytes(), &ParseOptions::default());
+    assert_eq!(
+        warnings,
+        vec![ParseWarning::NormalMismatch {
+            facet: 1,
+            angle: 90.0
+        }]
+    );
+}
@@ -0,0 +1,130 @@
+use stl::{AsciiOptions, StlFile, StlReader, StlWriter};
+
+const SOLIDS: &[u8] = b"solid first
+  facet normal 0 0 -1
+    outer loop
+      vertex 0 0 0
+      vertex 1 1 0
+      vertex 1 0 0
+    endloop
+  endfacet
+endsolid first
+solid second
+  facet normal 0 0 -1
+    outer loop
+      vertex 0 0 0
+      vertex 0 1 0
+      vertex 1 1 0
+    endloop
+  endfacet
+  facet normal 0 0 1
+    outer loop
+      vertex 0 0 1
+      vertex 1 1 1
+      vertex 0 1 1
+    endloop
+  endfacet
+endsolid second
+";
+
+#[test]
+fn parse_solids_keeps_names() {
+    let solids = StlFile::parse_ascii_solids(SOLIDS).unwrap();
+
+    assert_eq!(solids.len(), 2);
+    assert_eq!(solids[0].name(), "first");
+    assert_eq!(solids[0].facet_count(), 1);
+    assert_eq!(solids[1].name(), "second");
+    assert_eq!(solids[1].facet_count(), 2);
+}
+
+#[test]
+fn parse_merges_solids() {
+    let stl = StlFile::parse(SOLIDS).unwrap();
+
+    assert_eq!(stl.name(), "first");
+    assert_eq!(stl.facet_count(), 3);
+    assert_eq!(stl.attributes().len(), 3);
+}
+
+#[test]
+fn write_solids_round_trip() {
+    let solids = StlFile::parse_ascii_solids(SOLIDS).unwrap();
+
+    let mut buffer = Vec::new();
+    StlFile::write_ascii_solids(&solids, &mut buffer, &AsciiOptions::default()).unwrap();
+
+    assert_eq!(buffer, SOLIDS);
+}
+
+#[test]
+fn write_unnamed_solids_round_trip() {
+    // solids read from binary files have no names
+    let solids = StlFile::parse_ascii_solids(SOLIDS)
+        .unwrap()
+        .iter()
+        .map(|solid| {
+            let mut buffer = Vec::new();
+            solid.write_binary(&mut buffer).unwrap();
+            StlFile::parse(&buffer).unwrap()
+        })
+        .collect::<Vec<_>>();
+    assert!(solids.iter().all(|solid| solid.name().is_empty()));
+
+    let mut buffer = Vec::new();
+    StlFile::write_ascii_solids(&solids, &mut buffer, &AsciiOptions::default()).unwrap();
+
+    assert_eq!(StlFile::parse_ascii_solids(&buffer).unwrap(), solids);
+    assert_eq!(StlReader::new(buffer.as_slice()).unwrap().count(), 3);
+}
+
+#[test]
+fn trailing_content_is_ignored() {
+    let mut input = SOLIDS.to_vec();
+    input.extend_from_slice(b"exported by some tool\n");
+
+    assert_eq!(StlFile::parse_ascii_solids(&input).unwrap().len(), 2);
+    assert_eq!(StlReader::new(input.as_slice()).unwrap().count(), 3);
+}
+
+#[test]
+fn unnamed_endsolid() {
+    let input = b"solid a\nendsolid\nsolid b\nendsolid\n";
+    let solids = StlFile::parse_ascii_solids(input).unwrap();
+
+    assert_eq!(solids.len(), 2);
+    assert_eq!(solids[1].name(), "b");
+}
+
+#[test]
+fn reader_tracks_current_solid() {
+    let mut reader = StlReader::new(SOLIDS).unwrap();
+
+    reader.next().unwrap().unwrap();
+    assert_eq!(reader.name(), "first");
+
+    reader.next().unwrap().unwrap();
+    assert_eq!(reader.name(), "second");
+
+    reader.next().unwrap().unwrap();
+    assert!(reader.next().is_none());
+}
+
+#[test]
+fn writer_emits_multiple_solids() {
+    let solids = StlFile::parse_ascii_solids(SOLIDS).unwrap();
+
+    let mut writer = StlWriter::ascii(Vec::new(), "first").unwrap();
+    for triangle in solids[0].triangles() {
+        writer.write_triangle(triangle).unwrap();
+    }
+    writer.next_solid("second").unwrap();
+    for triangle in solids[1].triangles() {
+        writer.write_triangle(triangle).unwrap();
+    }
+
+    assert_eq!(writer.finish().unwrap(), SOLIDS);
+
+    let mut writer = StlWriter::binary_with_count(Vec::new(), 0).unwrap();
+    assert!(writer.next_solid("second").is_err());
+}
@@ -0,0 +1,89 @@
+mod common;
+
+use common::{point, triangle};
+use stl::{StlFile, Triangle};
+
+/// A unit cube whose lowest corner is at `offset` along each axis
+fn cube(offset: f32) -> Vec<Triangle> {
+    common::cube(1.0, [offset; 3])
+}
+
+#[test]
+fn closed_cube() {
+    let report = StlFile::from_triangles(cube(0.0)).topology_report();
+
+    assert!(report.boundary_edges.is_empty());
+    assert!(report.non_manifold_edges.is_empty());
+    assert!(report.non_manifold_vertices.is_empty());
+    assert!(report.inconsistent_edges.is_empty());
+    assert!(report.is_watertight());
+    assert!(report.is_consistently_oriented());
+}
+
+#[test]
+fn missing_facet() {
+    let mut triangles = cube(0.0);
+    let removed = triangles.remove(0);
+
+    let report = StlFile::from_triangles(triangles).topology_report();
+
+    assert_eq!(report.boundary_edges.len(), 3);
+    for (a, b) in &report.boundary_edges {
+        let corners = [removed.v0, removed.v1, removed.v2];
+        assert!(corners.contains(a) && corners.contains(b));
+    }
+
+    assert!(!report.is_watertight());
+    assert!(report.is_consistently_oriented());
+}
+
+#[test]
+fn flipped_facet() {
+    let mut stl = StlFile::from_triangles(cube(0.0));
+    stl.triangle_mut(3).unwrap().reverse_winding();
+
+    let report = stl.topology_report();
+    assert_eq!(report.inconsistent_edges.len(), 3);
+    assert!(report.is_watertight());
+    assert!(!report.is_consistently_oriented());
+
+    stl.orient_consistently();
+    assert!(stl.topology_report().is_consistently_oriented());
+}
+
+#[test]
+fn cubes_sharing_a_corner() {
+    let stl = StlFile::from_triangles(cube(0.0).into_iter().chain(cube(1.0)));
+    let report = stl.topology_report();
+
+    assert_eq!(report.non_manifold_vertices, vec![point([1.0, 1.0, 1.0])]);
+    assert!(report.boundary_edges.is_empty());
+    assert!(report.non_manifold_edges.is_empty());
+    assert!(!report.is_watertight());
+}
+
+#[test]
+fn edge_shared_by_three_facets() {
+    let (a, b) = (point([0.0, 0.0, 0.0]), point([1.0, 0.0, 0.0]));
+
+    let stl = StlFile::from_triangles(vec![
+        triangle(a, b, point([0.0, 1.0, 0.0])),
+        triangle(b, a, point([0.0, -1.0, 0.0])),
+        triangle(b, a, point([0.0, 0.0, 1.0])),
+    ]);
+    let report = stl.topology_report();
+
+    assert_eq!(report.non_manifold_edges, vec![(a, b)]);
+    assert_eq!(report.boundary_edges.len(), 6);
+    assert!(report.inconsistent_edges.is_empty());
+    assert!(!report.is_watertight());
+}
+
+#[test]
+fn edges_in_order_of_appearance() {
+    let [a, b, c, d] = [[0., 0., 0.], [1., 0., 0.], [1., 1., 0.], [0., 1., 0.]].map(point);
+    let report =
+        StlFile::from_triangles(vec![triangle(a, b, c), triangle(d, a, c)]).topology_report();
+
+    assert_eq!(report.boundary_edges, vec![(a, b), (b, c), (a, d), (c, d)]);
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use common::ASCII;
+use stl::{BinaryStlView, StlFile, Triangle};
+
+fn binary() -> (StlFile, Vec<u8>) {
+    let mut stl = StlFile::parse(ASCII).unwrap();
+    stl.attributes_mut().copy_from_slice(&[7, 9]);
+    stl.set_header([b'x'; 80]);
+
+    let mut buffer = Vec::new();
+    stl.write_binary(&mut buffer).unwrap();
+    (stl, buffer)
+}
+
+#[test]
+fn view_matches_parser() {
+    let (stl, buffer) = binary();
+    let view = BinaryStlView::new(&buffer).unwrap();
+
+    assert_eq!(view.facet_count(), 2);
+    assert_eq!(view.header(), &[b'x'; 80]);
+    assert_eq!(view.triangles().len(), 2);
+    assert_eq!(
+        view.triangles().collect::<Vec<Triangle>>(),
+        stl.triangles().collect::<Vec<Triangle>>()
+    );
+    assert_eq!(view.attributes().collect::<Vec<u16>>(), vec![7, 9]);
+    assert_eq!(view.triangle(1), stl.triangles().nth(1));
+    assert_eq!(view.attribute(1), Some(9));
+    assert_eq!(view.triangle(2), None);
+    assert_eq!(view.to_stl_file(), StlFile::parse(&buffer).unwrap());
+}
+
+#[test]
+fn view_rejects_truncated_input() {
+    let (_, buffer) = binary();
+
+    for len in 0..buffer.len() {
+        assert!(BinaryStlView::new(&buffer[..len]).is_err(), "len {}", len);
+    }
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn mmap_view() {
+    let (_, buffer) = binary();
+
+    let path = std::env::temp_dir().join(format!("stl-mmap-{}.stl", std::process::id()));
+    std::fs::write(&path, &buffer).unwrap();
+
+    // SAFETY: the file is private to this test and not modified while mapped
+    let file = unsafe { stl::MmapStl::from_path(&path) }.unwrap();
+    assert_eq!(file.view().to_stl_file(), StlFile::parse(&buffer).unwrap());
+
+    drop(file);
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,172 @@
+mod common;
+
+use common::{point, quad, triangle, FACES};
+use stl::StlFile;
+
+/// A unit cube where each face has its corners nudged by up to `jitter`
+fn jittered_cube(jitter: f32) -> StlFile {
+    let mut stl: StlFile = FACES
+        .iter()
+        .enumerate()
+        .flat_map(|(face, &[a, b, c, d])| {
+            let offset = jitter * face as f32 / 5.0;
+            quad([a, b, c, d].map(|[x, y, z]| point([x + offset, y, z - offset])))
+        })
+        .collect();
+
+    stl.recompute_normals();
+    stl
+}
+
+#[test]
+fn welds_jittered_vertices() {
+    let mut stl = jittered_cube(1e-4);
+
+    assert!(!stl.topology_report().is_watertight());
+    assert_eq!(
+        stl.index_buffer_vertex_only_with_tolerance(0.0)
+            .vertices()
+            .len(),
+        6 * 4 * 3
+    );
+    assert_eq!(
+        stl.index_buffer_vertex_only_with_tolerance(1e-3)
+            .vertices()
+            .len(),
+        8 * 3
+    );
+
+    assert_eq!(stl.weld_vertices(1e-3), 8);
+    assert!(stl.topology_report().is_watertight());
+    assert_eq!(
+        stl.index_buffer_vertex_only_with_tolerance(0.0)
+            .vertices()
+            .len(),
+        8 * 3
+    );
+}
+
+#[test]
+fn vertex_and_normal_with_tolerance() {
+    let stl = jittered_cube(1e-4);
+    let index_buffer = stl.index_buffer_vertex_and_normal_with_tolerance(1e-3);
+
+    // each corner is shared by three faces with different normals
+    assert_eq!(index_buffer.vertices().len(), 8 * 3 * 6);
+    assert_eq!(index_buffer.indices().len(), 36);
+
+    let vertices = index_buffer.vertices();
+    for (idx, vertex) in index_buffer
+        .indices()
+        .iter()
+        .zip(stl.vertex_and_normal_iterator())
+    {
+        let normal = &vertices[*idx as usize * 6 + 3..*idx as usize * 6 + 6];
+        assert_eq!(normal, [vertex.normal.i, vertex.normal.j, vertex.normal.k]);
+    }
+}
+
+#[test]
+fn points_straddling_grid_boundary() {
+    // two ulps apart, but rounded to neighboring points of the 1/1048576 grid
+    let a = 1.0 + 3.0 * f32::EPSILON;
+    let b = 1.0 + 5.0 * f32::EPSILON;
+    assert_ne!(point([a, 0., 0.]), point([b, 0., 0.]));
+
+    let stl: StlFile = vec![
+        triangle(point([a, 0., 0.]), point([2., 0., 0.]), point([2., 1., 0.])),
+        triangle(point([b, 0., 0.]), point([0., 1., 0.]), point([0., 0., 0.])),
+    ]
+    .into_iter()
+    .collect();
+
+    assert_eq!(stl.clone().split_islands().len(), 2);
+    assert_eq!(stl.clone().split_islands_with_tolerance(1e-6).len(), 1);
+    assert_eq!(
+        stl.index_buffer_vertex_only_with_tolerance(1e-6).indices(),
+        [0, 1, 2, 0, 3, 4]
+    );
+}
+
+#[test]
+fn small_units() {
+    // two triangles a tenth of a micrometre apart, in metres
+    let stl: StlFile = vec![
+        triangle(
+            point([0., 0., 0.]),
+            point([1e-5, 0., 0.]),
+            point([0., 1e-5, 0.]),
+        ),
+        triangle(
+            point([0., 0., 1e-7]),
+            point([1e-5, 0., 1e-7]),
+            point([0., 1e-5, 1e-7]),
+        ),
+    ]
+    .into_iter()
+    .collect();
+
+    assert_eq!(stl.clone().split_islands().len(), 1);
+
+    let islands = stl.clone().split_islands_with_tolerance(1e-8);
+    assert_eq!(islands.len(), 2);
+    assert_eq!(islands[1].triangle(0), stl.triangle(1));
+
+    assert_eq!(stl.split_islands_with_tolerance(1e-5).len(), 1);
+}
+
+#[test]
+fn clusters_do_not_chain() {
+    let stl: StlFile = vec![triangle(
+        point([0., 0., 0.]),
+        point([0.9, 0., 0.]),
+        point([1.8, 0., 0.]),
+    )]
+    .into_iter()
+    .collect();
+
+    let index_buffer = stl.index_buffer_vertex_only_with_tolerance(1.0);
+    assert_eq!(index_buffer.indices(), [0, 0, 1]);
+    assert_eq!(index_buffer.vertices(), [0., 0., 0., 1.8, 0., 0.]);
+}
+
+#[test]
+fn zero_tolerance_welds_identical_vertices() {
+    let mut stl: StlFile = vec![
+        triangle(
+            point([0., 0., 0.]),
+            point([1., 0., 0.]),
+            point([0., 1., 0.]),
+        ),
+        triangle(
+            point([-0., 0., 0.]),
+            point([1e-7, 1., 0.]),
+            point([0., 1., 0.]),
+        ),
+    ]
+    .into_iter()
+    .collect();
+
+    assert_eq!(
+        stl.index_buffer_vertex_only_with_tolerance(0.0).indices(),
+        [0, 1, 2, 0, 3, 2]
+    );
+    assert_eq!(stl.weld_vertices(0.0), 4);
+}
+
+#[test]
+fn non_finite_vertices_are_kept_apart() {
+    let nan = point([f32::NAN, 0., 0.]);
+    let stl: StlFile = vec![triangle(nan, nan, point([0., 0., 0.]))]
+        .into_iter()
+        .collect();
+
+    assert_eq!(
+        stl.index_buffer_vertex_only_with_tolerance(1.0).indices(),
+        [0, 1, 2]
+    );
+    assert_eq!(
+        stl.index_buffer_vertex_only_with_tolerance(0.0).indices(),
+        [0, 1, 2]
+    );
+}
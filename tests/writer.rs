@@ -0,0 +1,181 @@
+use std::io::Cursor;
+
+use stl::{AsciiOptions, FloatFormat, StlFile, StlFormat, StlReader, StlWriter, Triangle};
+
+const ASCII: &[u8] = b"solid cube
+  facet normal 0 0 -1
+    outer loop
+      vertex 0 0 0
+      vertex 1 1 0
+      vertex 1 0 0
+    endloop
+  endfacet
+  facet normal 0 0 -1
+    outer loop
+      vertex 0 0 0
+      vertex 0 1 0
+      vertex 1 1 0.5
+    endloop
+  endfacet
+endsolid cube
+";
+
+fn triangles() -> Vec<Triangle> {
+    StlFile::parse(ASCII).unwrap().triangles().collect()
+}
+
+#[test]
+fn binary_patches_facet_count() {
+    let mut writer = StlWriter::binary(Cursor::new(Vec::new())).unwrap();
+    for triangle in triangles() {
+        writer.write_triangle(triangle).unwrap();
+    }
+    let buffer = writer.finish().unwrap().into_inner();
+
+    assert_eq!(buffer.len(), 84 + 2 * 50);
+    assert_eq!(&buffer[80..84], &2u32.to_le_bytes());
+
+    let parsed: Vec<Triangle> = StlFile::parse_binary(&buffer)
+        .unwrap()
+        .triangles()
+        .collect();
+    assert_eq!(parsed, triangles());
+}
+
+#[test]
+fn binary_patch_respects_start_position() {
+    let mut cursor = Cursor::new(vec![0xff; 10]);
+    cursor.set_position(10);
+
+    let mut writer = StlWriter::binary(cursor).unwrap();
+    writer.write_triangle(triangles()[0]).unwrap();
+    let buffer = writer.finish().unwrap().into_inner();
+
+    assert_eq!(
+        StlFile::parse_binary(&buffer[10..]).unwrap().facet_count(),
+        1
+    );
+}
+
+#[test]
+fn binary_with_declared_count() {
+    let mut buffer = Vec::new();
+    let mut writer = StlWriter::binary_with_count(&mut buffer, 2).unwrap();
+    for triangle in triangles() {
+        writer.write_triangle(triangle).unwrap();
+    }
+    assert!(writer.write_triangle(triangles()[0]).is_err());
+    writer.finish().unwrap();
+
+    assert_eq!(StlFile::parse_binary(&buffer).unwrap().facet_count(), 2);
+
+    let mut writer = StlWriter::binary_with_count(Vec::new(), 2).unwrap();
+    writer.write_triangle(triangles()[0]).unwrap();
+    assert!(writer.finish().is_err());
+}
+
+#[test]
+fn ascii_round_trip() {
+    let mut writer = StlWriter::ascii(Vec::new(), "cube").unwrap();
+    assert_eq!(writer.format(), StlFormat::Ascii);
+    for triangle in triangles() {
+        writer.write_triangle(triangle).unwrap();
+    }
+    let buffer = writer.finish().unwrap();
+
+    let parsed: Vec<Triangle> = StlReader::new(buffer.as_slice())
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(parsed, triangles());
+}
+
+#[test]
+fn write_ascii_is_diffable() {
+    let mut buffer = Vec::new();
+    StlFile::parse(ASCII)
+        .unwrap()
+        .write_ascii(&mut buffer, "cube")
+        .unwrap();
+
+    assert_eq!(buffer, ASCII);
+}
+
+#[test]
+fn write_ascii_fixed_precision_round_trips() {
+    let stl = StlFile::parse(ASCII).unwrap();
+
+    for float_format in &[FloatFormat::Fixed(6), FloatFormat::Scientific(6)] {
+        let options = AsciiOptions {
+            float_format: *float_format,
+            indent: "\t".to_owned(),
+        };
+
+        let mut buffer = Vec::new();
+        stl.write_ascii_with_options(&mut buffer, "cube", &options)
+            .unwrap();
+
+        assert_eq!(StlFile::parse(&buffer).unwrap(), stl);
+    }
+}
+
+#[test]
+fn header_and_name_round_trip() {
+    let mut stl = StlFile::parse(ASCII).unwrap();
+    assert_eq!(stl.name(), "cube");
+    assert_eq!(stl.header(), &[0; 80]);
+
+    let mut header = [b' '; 80];
+    header[..19].copy_from_slice(b"COLOR=\x10\x20\x30\xff exported");
+    stl.set_header(header);
+
+    let mut buffer = Vec::new();
+    stl.write_binary(&mut buffer).unwrap();
+
+    assert_eq!(StlFile::parse(&buffer).unwrap().header(), &header);
+    assert_eq!(StlReader::new(buffer.as_slice()).unwrap().header(), &header);
+
+    stl.set_name("renamed");
+    let mut buffer = Vec::new();
+    stl.write_ascii(&mut buffer, stl.name()).unwrap();
+
+    assert_eq!(StlFile::parse(&buffer).unwrap().name(), "renamed");
+    assert_eq!(StlReader::new(buffer.as_slice()).unwrap().name(), "renamed");
+}
+
+#[test]
+fn empty_name_round_trips() {
+    let mut buffer = Vec::new();
+    StlFile::parse(ASCII)
+        .unwrap()
+        .write_ascii(&mut buffer, "")
+        .unwrap();
+    assert!(buffer.starts_with(b"solid \n"));
+
+    let stl = StlFile::parse(&buffer).unwrap();
+    assert_eq!(stl.name(), "");
+    assert_eq!(stl.triangles().collect::<Vec<_>>(), triangles());
+
+    let reader = StlReader::new(buffer.as_slice()).unwrap();
+    assert_eq!(reader.name(), "");
+    assert_eq!(reader.collect::<Result<Vec<_>, _>>().unwrap(), triangles());
+}
+
+#[test]
+fn names_with_spaces_are_written_as_one_token() {
+    let mut buffer = Vec::new();
+    StlFile::parse(ASCII)
+        .unwrap()
+        .write_ascii(&mut buffer, "my part\tv2")
+        .unwrap();
+    assert!(buffer.starts_with(b"solid my_part_v2\n"));
+    assert!(buffer.ends_with(b"endsolid my_part_v2\n"));
+
+    let stl = StlFile::parse(&buffer).unwrap();
+    assert_eq!(stl.name(), "my_part_v2");
+    assert_eq!(stl.triangles().collect::<Vec<_>>(), triangles());
+
+    let reader = StlReader::new(buffer.as_slice()).unwrap();
+    assert_eq!(reader.name(), "my_part_v2");
+    assert_eq!(reader.collect::<Result<Vec<_>, _>>().unwrap(), triangles());
+}